@@ -1,18 +1,63 @@
 use eframe::egui::{self, Stroke};
+use rfd::FileDialog;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
-use crate::download::{start_download, update_ytdlp};
+use crate::desktop;
+use crate::download::{
+    check_subscription, cleanup_partial_files, ffmpeg_available, fetch_info, start_download,
+    update_ytdlp, DownloadControl, StopRequest,
+};
+use crate::events::{AppEvent, DownloadEvent, InfoEvent, Stage, SubscriptionEvent, UpdaterEvent};
+use crate::history::{History, HistoryEntry, HistoryId, Outcome};
 use crate::localizations::Localizations;
-use crate::models::AppState;
+use crate::archive::{
+    append_to_archive, archive_key, archive_path, export_archive, import_archive, load_archive,
+    load_entries, remove_from_archive,
+};
+use crate::errors::{ErrorKind, Remedy};
+use crate::models::{
+    AppState, Collision, CollisionPolicy, DownloadFormat, DownloadOptions, EmbedSettings, FormatChooser,
+    FormatSelection, JobId, JobStatus, PersistedJob, PlaylistEntry, PlaylistPreview, Preview,
+    Settings, Tab,
+};
+use crate::progress::overall_progress;
+use crate::records::{self, Record};
+use crate::storage::{self, unix_now};
+use crate::subscriptions::{Subscription, SubscriptionId, SubscriptionList};
+use crate::template;
 use crate::theme::*;
-use crate::ui;
+use crate::ui::{
+    self, ArchiveAction, HistoryAction, JobAction, PreviewAction, RecoveryAction,
+    SubscriptionAction,
+};
+
+const JOBS_FILE: &str = "jobs.json";
+const SUBSCRIPTIONS_FILE: &str = "subscriptions.json";
+const HISTORY_FILE: &str = "history.json";
+const SETTINGS_FILE: &str = "settings.json";
+// How long closing the app waits for stopped downloads to exit
+const EXIT_TIMEOUT: Duration = Duration::from_secs(3);
+
+struct Worker {
+    handle: JoinHandle<()>,
+    control: DownloadControl,
+}
 
 pub struct YtdlApp {
     pub state: AppState,
     localizer: Localizations,
-    status_sender: Sender<(bool, String)>,
-    status_receiver: Option<Receiver<(bool, String)>>,
+    event_sender: Sender<AppEvent>,
+    event_receiver: Option<Receiver<AppEvent>>,
+    workers: HashMap<JobId, Worker>,
+    // Last snapshot written to JOBS_FILE, to avoid rewriting it every frame
+    persisted_jobs: Vec<PersistedJob>,
+    // Subscriptions as last written to disk
+    persisted_subscriptions: Vec<Subscription>,
+    persisted_settings: Settings,
 }
 
 impl Default for YtdlApp {
@@ -26,24 +71,39 @@ impl YtdlApp {
         let (tx, rx) = mpsc::channel();
         let localizer = Localizations::new();
 
-        let mut state = AppState::default();
-        state.status = localizer
-            .lookup_single_language("status-ready", None)
-            .unwrap_or_else(|| "Ready".to_string());
+        let recovered_jobs: Vec<PersistedJob> = storage::load_json(JOBS_FILE).unwrap_or_default();
+        let subscriptions: Vec<Subscription> =
+            storage::load_json(SUBSCRIPTIONS_FILE).unwrap_or_default();
+        let history: Vec<HistoryEntry> = storage::load_json(HISTORY_FILE).unwrap_or_default();
+        let mut settings: Settings = storage::load_json(SETTINGS_FILE).unwrap_or_default();
+        settings.clamp();
+
+        let state = AppState {
+            status: localizer
+                .lookup_single_language("status-ready", None)
+                .unwrap_or_else(|| "Ready".to_string()),
+            recovered_jobs: recovered_jobs.clone(),
+            subscriptions: SubscriptionList::from_saved(subscriptions.clone()),
+            history: History::from_saved(history),
+            template_draft: settings.output_template.clone(),
+            settings: settings.clone(),
+            ffmpeg_available: ffmpeg_available(),
+            ..Default::default()
+        };
 
         Self {
             state,
             localizer,
-            status_sender: tx,
-            status_receiver: Some(rx),
+            event_sender: tx,
+            event_receiver: Some(rx),
+            workers: HashMap::new(),
+            persisted_jobs: recovered_jobs,
+            persisted_subscriptions: subscriptions,
+            persisted_settings: settings,
         }
     }
 
     pub fn start_download(&mut self, ctx: &egui::Context) {
-        if self.state.is_downloading {
-            return;
-        }
-
         if self.state.url.trim().is_empty() {
             self.state.error = Some(
                 self.localizer
@@ -68,33 +128,633 @@ impl YtdlApp {
             }
         }
 
-        self.state.is_downloading = true;
-        self.state.progress = 0.0;
         self.state.error = None;
         self.state.last_error = None;
-        self.state.download_speed = String::new();
-        self.state.eta = String::new();
+
+        let url = self.state.url.trim().to_string();
+
+        // Only yt-dlp can tell whether the URL is a playlist, whose entries
+        // are picked before anything is queued
+        if !self.state.preview.as_ref().is_some_and(|preview| preview.url == url) {
+            self.fetch_info(ctx);
+        }
+        if let Some(preview) = self.state.preview.as_mut().filter(|p| p.url == url && p.loading) {
+            preview.start_when_ready = true;
+            self.state.status = self
+                .localizer
+                .lookup_single_language("waiting-for-info", None)
+                .unwrap_or_else(|| "Fetching info, the download starts once it arrives".to_string());
+            return;
+        }
+        let preview = self.state.preview.as_ref().filter(|preview| preview.url == url);
+
+        if let Some(playlist) = preview.and_then(|preview| preview.playlist.as_ref()) {
+            // Every ticked entry becomes its own job sharing format and directory
+            let entries: Vec<(String, Option<String>)> = playlist
+                .selected_entries()
+                .filter_map(|entry| Some((entry.url.clone()?, entry.title.clone())))
+                .collect();
+            if entries.is_empty() {
+                self.state.error = Some(
+                    self.localizer
+                        .lookup_single_language("no-entries-selected", None)
+                        .unwrap_or_else(|| "No playlist entries selected".to_string()),
+                );
+                return;
+            }
+            for (entry_url, title) in entries {
+                let id = self.state.queue.enqueue(
+                    entry_url,
+                    self.state.format,
+                    self.state.download_dir.clone(),
+                );
+                if let Some(job) = self.state.queue.get_mut(id) {
+                    job.title = title;
+                    job.capture_settings(&self.state.settings);
+                }
+            }
+        } else {
+            let metadata = preview.and_then(|preview| preview.metadata.as_ref());
+            let title = metadata.map(|metadata| metadata.title.clone());
+            let extractor = metadata.and_then(|metadata| metadata.extractor_key.clone());
+            let media_id = metadata.map(|metadata| metadata.id.clone());
+            let duration = metadata.and_then(|metadata| metadata.duration);
+            let format_spec = preview
+                .and_then(|preview| preview.selected_format.as_ref())
+                .and_then(FormatSelection::spec);
+            let rerun = self.state.rerun_url.take().is_some_and(|rerun| rerun == url);
+            let id = self
+                .state
+                .queue
+                .enqueue(url, self.state.format, self.state.download_dir.clone());
+            if let Some(job) = self.state.queue.get_mut(id) {
+                job.ignore_archive = rerun;
+                job.title = title;
+                job.extractor = extractor;
+                job.media_id = media_id;
+                job.duration = duration;
+                job.format_spec = format_spec;
+                job.capture_settings(&self.state.settings);
+            }
+        }
+        self.state.url.clear();
+        self.state.preview = None;
         self.state.status = self
             .localizer
-            .lookup_single_language("status-downloading", None)
-            .unwrap_or_else(|| "Downloading...".to_string());
+            .lookup_single_language("job-added", None)
+            .unwrap_or_else(|| "Added to queue".to_string());
 
-        let format = self.state.format;
-        let url = self.state.url.clone();
-        let download_dir = self.state.download_dir.clone();
-        let tx = self.status_sender.clone();
+        self.pump_queue();
+        ctx.request_repaint();
+    }
 
-        start_download(url, format, download_dir, tx);
+    pub fn fetch_info(&mut self, ctx: &egui::Context) {
+        let url = self.state.url.trim().to_string();
+        if url.is_empty() {
+            self.state.error = Some(
+                self.localizer
+                    .lookup_single_language("enter-url", None)
+                    .unwrap_or_else(|| "Please enter a URL".to_string()),
+            );
+            self.state.last_error = self.state.error.clone();
+            return;
+        }
+
+        // yt-dlp can't run while it is being replaced; process_events starts
+        // the fetch once the update is done
+        let deferred = self.state.is_updating;
+        self.state.preview = Some(Preview {
+            url: url.clone(),
+            loading: true,
+            deferred,
+            start_when_ready: false,
+            metadata: None,
+            playlist: None,
+            thumbnail: None,
+            error: None,
+            chooser: FormatChooser::default(),
+            selected_format: None,
+        });
+        if !deferred {
+            fetch_info(
+                url,
+                self.state.settings.cookies_file.clone(),
+                self.event_sender.clone(),
+            );
+        }
         ctx.request_repaint();
     }
 
+    fn start_deferred_fetch(&mut self) {
+        if self.state.is_updating {
+            return;
+        }
+        let Some(preview) = self.state.preview.as_mut().filter(|p| p.deferred) else {
+            return;
+        };
+        preview.deferred = false;
+        fetch_info(
+            preview.url.clone(),
+            self.state.settings.cookies_file.clone(),
+            self.event_sender.clone(),
+        );
+    }
+
+    // Whether anything is running yt-dlp, which has to finish before the
+    // managed binary can be replaced
+    fn ytdlp_in_use(&self) -> bool {
+        !self.workers.is_empty()
+            || self.state.preview.as_ref().is_some_and(|p| p.loading && !p.deferred)
+            || self.state.subscriptions.is_checking()
+    }
+
+    fn handle_info_event(&mut self, event: InfoEvent, ctx: &egui::Context) {
+        let url = match &event {
+            InfoEvent::Fetched { url, .. }
+            | InfoEvent::Playlist { url, .. }
+            | InfoEvent::Thumbnail { url, .. }
+            | InfoEvent::Failed { url, .. } => url,
+        };
+        let Some(preview) = self.state.preview.as_mut().filter(|p| &p.url == url) else {
+            return;
+        };
+
+        // Unless the URL was changed in the meantime
+        let requested = preview.start_when_ready && preview.url == self.state.url.trim();
+        match event {
+            InfoEvent::Fetched { metadata, .. } => {
+                preview.loading = false;
+                preview.metadata = Some(*metadata);
+                if requested {
+                    self.start_download(ctx);
+                }
+            }
+            InfoEvent::Playlist { playlist, .. } => {
+                preview.loading = false;
+                preview.playlist = Some(PlaylistPreview::new(*playlist));
+                if requested {
+                    self.state.status = self
+                        .localizer
+                        .lookup_single_language("playlist-detected", None)
+                        .unwrap_or_else(|| "Playlist detected, choose the entries to download".to_string());
+                }
+            }
+            InfoEvent::Thumbnail { image, .. } => {
+                preview.thumbnail =
+                    Some(ctx.load_texture("preview-thumbnail", image, egui::TextureOptions::LINEAR));
+            }
+            InfoEvent::Failed { message, .. } => {
+                preview.loading = false;
+                preview.error = Some(message);
+                // Queued as before info was fetched first; the download
+                // itself reports what is wrong with the URL
+                if requested {
+                    self.start_download(ctx);
+                }
+            }
+        }
+    }
+
+    // Starts queued jobs until the concurrency limit is reached
+    fn pump_queue(&mut self) {
+        // Nothing new starts while the managed binary is, or is about to be, replaced
+        if self.state.is_updating || self.state.update_pending {
+            return;
+        }
+
+        let now = Instant::now();
+        while self.workers.len() < self.state.settings.max_concurrent {
+            let Some(id) = self.state.queue.next_queued(now) else {
+                return;
+            };
+            let Some(job) = self.state.queue.get_mut(id) else {
+                return;
+            };
+
+            // Looked for again in case it was installed since the last check
+            let needs_ffmpeg = job.format == DownloadFormat::MP3
+                || job.subtitles.is_some()
+                || job.embed.any()
+                || job.split_chapters;
+            if needs_ffmpeg && !self.state.ffmpeg_available {
+                self.state.ffmpeg_available = ffmpeg_available();
+            }
+            // Extracting the audio is ffmpeg's job, so an MP3 download can't
+            // do without it
+            if job.format == DownloadFormat::MP3 && !self.state.ffmpeg_available {
+                let message = self
+                    .localizer
+                    .lookup_single_language("error-ffmpeg-missing", None)
+                    .unwrap_or_else(|| {
+                        "ffmpeg is required for this download but was not found.".to_string()
+                    });
+                self.handle_job_event(
+                    id,
+                    DownloadEvent::Error {
+                        kind: ErrorKind::FfmpegMissing,
+                        message,
+                    },
+                );
+                self.record_history(id);
+                continue;
+            }
+
+            // Progress is kept so a resumed job does not jump back to zero
+            job.status = JobStatus::Downloading;
+            job.retry_at = None;
+            job.run_started = Some((now, job.progress));
+            job.started_at.get_or_insert_with(unix_now);
+            job.clear_transfer_stats();
+
+            // yt-dlp fails the whole job when an embedding, converting or
+            // splitting step can't find ffmpeg, so the media is downloaded
+            // without them instead
+            let mut subtitles = job.subtitles.clone();
+            let mut embed = job.embed;
+            let mut split_chapters = job.split_chapters;
+            if (subtitles.is_some() || embed.any() || split_chapters) && !self.state.ffmpeg_available {
+                if let Some(subtitles) = &mut subtitles {
+                    subtitles.embed = false;
+                }
+                embed = EmbedSettings::default();
+                split_chapters = false;
+                let warning = self
+                    .localizer
+                    .lookup_single_language("embed-skipped", None)
+                    .unwrap_or_else(|| {
+                        "ffmpeg was not found, so nothing was embedded, converted or split".to_string()
+                    });
+                if !job.warnings.contains(&warning) {
+                    job.warnings.push(warning);
+                }
+            }
+
+            let control = DownloadControl::default();
+            let handle = start_download(
+                id,
+                job.url.clone(),
+                job.format,
+                job.download_dir.clone(),
+                DownloadOptions {
+                    cookies_file: job.cookies_file.clone(),
+                    format_spec: job.format_spec.clone(),
+                    download_archive: (!job.ignore_archive).then(archive_path),
+                    output_template: Some(
+                        job.output_name.clone().unwrap_or_else(|| job.output_template.clone()),
+                    ),
+                    collision_policy: job.collision_policy,
+                    only_ids: job.only_ids.clone(),
+                    subtitles,
+                    convert_subtitles: self.state.ffmpeg_available,
+                    embed,
+                    split_chapters,
+                },
+                control.clone(),
+                self.event_sender.clone(),
+            );
+            self.workers.insert(id, Worker { handle, control });
+        }
+    }
+
+    pub fn cancel_job(&mut self, id: JobId) {
+        if let Some(worker) = self.workers.get(&id) {
+            // The worker reports the cancellation once yt-dlp has exited
+            worker.control.request_stop(StopRequest::Cancel {
+                delete_partial: self.state.settings.delete_partial_on_cancel,
+            });
+        } else if let Some(job) = self.state.queue.get_mut(id) {
+            match job.status {
+                JobStatus::Queued => job.status = JobStatus::Cancelled,
+                JobStatus::Paused => {
+                    if self.state.settings.delete_partial_on_cancel {
+                        cleanup_partial_files(&job.destinations, &job.intermediates);
+                    }
+                    job.status = JobStatus::Cancelled;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub fn pause_job(&mut self, id: JobId) {
+        if let Some(worker) = self.workers.get(&id) {
+            worker.control.request_stop(StopRequest::Pause);
+        } else if let Some(job) = self.state.queue.get_mut(id) {
+            if job.status == JobStatus::Queued {
+                job.status = JobStatus::Paused;
+            }
+        }
+    }
+
+    pub fn resume_job(&mut self, id: JobId) {
+        if let Some(job) = self.state.queue.get_mut(id) {
+            if job.status == JobStatus::Paused {
+                job.status = JobStatus::Queued;
+            }
+        }
+        self.pump_queue();
+    }
+
+    fn handle_recovery(&mut self, action: RecoveryAction) {
+        let recovered = std::mem::take(&mut self.state.recovered_jobs);
+        match action {
+            RecoveryAction::ResumeAll => {
+                for persisted in recovered {
+                    self.state.queue.restore(persisted);
+                }
+                self.pump_queue();
+            }
+            RecoveryAction::Discard => {
+                if self.state.settings.delete_partial_on_cancel {
+                    for persisted in &recovered {
+                        cleanup_partial_files(&persisted.destinations, &persisted.intermediates);
+                    }
+                }
+            }
+        }
+    }
+
+    // Keeps JOBS_FILE in sync with the jobs that still need to run,
+    // including recovered ones the user has not decided about yet
+    fn persist_jobs(&mut self) {
+        let mut snapshot = self.state.recovered_jobs.clone();
+        snapshot.extend(self.state.queue.unfinished());
+
+        if snapshot == self.persisted_jobs {
+            return;
+        }
+
+        if let Err(e) = storage::save_json(JOBS_FILE, &snapshot) {
+            log::error!("Failed to save unfinished downloads: {}", e);
+            return;
+        }
+        self.persisted_jobs = snapshot;
+    }
+
+    // Adds a job to the history once it reaches a final state. Jobs waiting
+    // for an automatic retry are not final yet
+    fn record_history(&mut self, id: JobId) {
+        let Some(job) = self.state.queue.get_mut(id) else {
+            return;
+        };
+        if job.is_finished() && !job.in_history {
+            job.in_history = true;
+            self.state.history.record(job);
+        }
+    }
+
+    fn persist_history(&mut self) {
+        if !self.state.history.is_dirty() {
+            return;
+        }
+        if let Err(e) = storage::save_json(HISTORY_FILE, &self.state.history.entries()) {
+            log::error!("Failed to save history: {}", e);
+            return;
+        }
+        self.state.history.mark_saved();
+    }
+
+    fn persist_settings(&mut self) {
+        if self.state.settings == self.persisted_settings {
+            return;
+        }
+        if let Err(e) = storage::save_json(SETTINGS_FILE, &self.state.settings) {
+            log::error!("Failed to save settings: {}", e);
+            return;
+        }
+        self.persisted_settings = self.state.settings.clone();
+    }
+
+    fn persist_subscriptions(&mut self) {
+        let snapshot = self.state.subscriptions.subscriptions();
+        if snapshot == self.persisted_subscriptions.as_slice() {
+            return;
+        }
+
+        if let Err(e) = storage::save_json(SUBSCRIPTIONS_FILE, &snapshot) {
+            log::error!("Failed to save subscriptions: {}", e);
+            return;
+        }
+        self.persisted_subscriptions = snapshot.to_vec();
+    }
+
+    fn add_subscription(&mut self) {
+        let form = &mut self.state.subscription_form;
+        let url = form.url.trim().to_string();
+        if url.is_empty() {
+            return;
+        }
+        let download_dir = if form.download_dir.trim().is_empty() {
+            self.state.download_dir.clone()
+        } else {
+            form.download_dir.trim().to_string()
+        };
+        let name = match form.name.trim() {
+            "" => url.clone(),
+            name => name.to_string(),
+        };
+
+        let id = self.state.subscriptions.add(
+            url,
+            name,
+            form.format,
+            download_dir,
+            form.filters.clone(),
+            form.skip_existing,
+        );
+        form.url.clear();
+        form.name.clear();
+        self.check_subscription(id);
+    }
+
+    fn check_subscription(&mut self, id: SubscriptionId) {
+        let cookies_file = self.state.settings.cookies_file.clone();
+        let updating = self.state.is_updating || self.state.update_pending;
+        let Some(subscription) = self.state.subscriptions.get_mut(id) else {
+            return;
+        };
+        if subscription.checking {
+            return;
+        }
+        // Left to check_due_subscriptions, which waits for the update
+        if updating {
+            subscription.check_requested = true;
+            return;
+        }
+        subscription.check_requested = false;
+        subscription.checking = true;
+        subscription.last_attempt = Some(unix_now());
+        subscription.error = None;
+        check_subscription(
+            id,
+            subscription.url.clone(),
+            subscription.filters.max_items,
+            cookies_file,
+            self.event_sender.clone(),
+        );
+    }
+
+    // Starts at most one scheduled check at a time
+    fn check_due_subscriptions(&mut self) {
+        let updating = self.state.is_updating || self.state.update_pending;
+        if updating || self.state.subscriptions.is_checking() {
+            return;
+        }
+        let interval_secs = self.state.settings.check_interval_minutes * 60;
+        if let Some(id) = self.state.subscriptions.next_due(unix_now(), interval_secs) {
+            self.check_subscription(id);
+        }
+    }
+
+    fn handle_subscription_event(&mut self, event: SubscriptionEvent) {
+        match event {
+            SubscriptionEvent::Checked { id, playlist } => {
+                let Some(subscription) = self.state.subscriptions.get_mut(id) else {
+                    return;
+                };
+                subscription.checking = false;
+                let first_check = subscription.last_checked.is_none();
+
+                let archive = load_archive();
+                let key = |entry: &PlaylistEntry| {
+                    entry
+                        .ie_key
+                        .as_deref()
+                        .map(|extractor| archive_key(extractor, &entry.id))
+                };
+                let new_entries: Vec<&PlaylistEntry> = playlist
+                    .entries
+                    .iter()
+                    .filter(|entry| {
+                        entry.url.as_ref().is_some_and(|url| !self.state.queue.contains_url(url))
+                            && key(entry).map_or(true, |key| !archive.contains(&key))
+                            && subscription.filters.matches(entry)
+                    })
+                    .collect();
+
+                if first_check && subscription.skip_existing {
+                    // Only uploads after this point count as new. Until they are
+                    // recorded, the next check is still treated as the first one
+                    let keys: Vec<String> = new_entries.iter().filter_map(|entry| key(entry)).collect();
+                    match append_to_archive(&keys) {
+                        Ok(()) => subscription.last_checked = Some(unix_now()),
+                        Err(e) => subscription.error = Some(e),
+                    }
+                    subscription.new_count = 0;
+                    return;
+                }
+
+                subscription.last_checked = Some(unix_now());
+                subscription.new_count = new_entries.len();
+                let (format, download_dir) = (subscription.format, subscription.download_dir.clone());
+                let name = subscription.name.clone();
+                let count = new_entries.len();
+                for entry in new_entries {
+                    let Some(url) = entry.url.clone() else {
+                        continue;
+                    };
+                    let job_id = self.state.queue.enqueue(url, format, download_dir.clone());
+                    if let Some(job) = self.state.queue.get_mut(job_id) {
+                        job.title = entry.title.clone();
+                        job.subscription = Some(id);
+                        job.capture_settings(&self.state.settings);
+                    }
+                }
+
+                if count > 0 {
+                    self.state.status = format!(
+                        "{}: {} {}",
+                        name,
+                        count,
+                        self.localizer
+                            .lookup_single_language("new-videos", None)
+                            .unwrap_or_else(|| "new videos".to_string())
+                    );
+                }
+            }
+            SubscriptionEvent::Failed { id, message } => {
+                if let Some(subscription) = self.state.subscriptions.get_mut(id) {
+                    subscription.checking = false;
+                    subscription.error = Some(message);
+                }
+            }
+        }
+    }
+
+    fn open_output(&mut self, id: JobId, reveal: bool) {
+        if let Some(path) = self.state.queue.get(id).and_then(|job| job.output_path.clone()) {
+            self.open_file(&path, reveal);
+        }
+    }
+
+    // Opens a downloaded file or shows it in its folder. Whether it still
+    // exists is only checked here, since it may have been moved or deleted
+    fn open_file(&mut self, path: &Path, reveal: bool) {
+        let result = if !path.exists() {
+            Err(self
+                .localizer
+                .lookup_single_language("file-missing", None)
+                .unwrap_or_else(|| "The file was moved or deleted".to_string()))
+        } else if reveal {
+            desktop::reveal_in_file_manager(path, self.event_sender.clone());
+            Ok(())
+        } else {
+            desktop::open_path(path)
+        };
+
+        if let Err(e) = result {
+            self.state.error = Some(e.clone());
+            self.state.last_error = Some(e);
+        }
+    }
+
+    pub fn retry_job(&mut self, id: JobId) {
+        self.state.queue.retry(id);
+        self.pump_queue();
+    }
+
+    fn apply_remedy(&mut self, id: JobId, remedy: Remedy, ctx: &egui::Context) {
+        match remedy {
+            Remedy::UpdateYtdlp => {
+                // Queued jobs wait for the update to finish before starting
+                self.state.queue.retry(id);
+                self.update_ytdlp(ctx);
+            }
+            Remedy::ProvideCookies => {
+                if let Some(path) = FileDialog::new()
+                    .add_filter("cookies.txt", &["txt"])
+                    .pick_file()
+                {
+                    let path = path.to_string_lossy().to_string();
+                    if let Some(job) = self.state.queue.get_mut(id) {
+                        job.cookies_file = Some(path.clone());
+                    }
+                    self.state.settings.cookies_file = Some(path);
+                    self.retry_job(id);
+                }
+            }
+        }
+    }
+
     pub fn update_ytdlp(&mut self, ctx: &egui::Context) {
-        if self.state.is_downloading {
+        if self.state.is_updating {
+            return;
+        }
+
+        // The managed binary is replaced in place, so wait for running jobs,
+        // fetches and checks; process_events starts the update once they are done
+        if self.ytdlp_in_use() {
+            self.state.update_pending = true;
+            self.state.status = self
+                .localizer
+                .lookup_single_language("update-pending", None)
+                .unwrap_or_else(|| "yt-dlp will be updated once running downloads finish".to_string());
             return;
         }
 
-        self.state.is_downloading = true;
-        self.state.progress = 0.0;
+        self.state.update_pending = false;
+        self.state.is_updating = true;
+        self.state.updater_progress = None;
         self.state.error = None;
         self.state.last_error = None;
         self.state.status = self
@@ -102,13 +762,13 @@ impl YtdlApp {
             .lookup_single_language("status-updating", None)
             .unwrap_or_else(|| "Updating yt-dlp...".to_string());
 
-        let tx = self.status_sender.clone();
+        let tx = self.event_sender.clone();
         update_ytdlp(tx);
         ctx.request_repaint();
     }
 
     pub fn update_ui(&mut self, ctx: &egui::Context) {
-        self.process_status_updates(ctx);
+        self.process_events(ctx);
 
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.heading(
@@ -117,106 +777,504 @@ impl YtdlApp {
                     .unwrap_or_else(|| "YouTube Downloader".to_string()),
             );
 
-            ui.add_space(20.0);
+            ui.add_space(10.0);
+            let previous_tab = self.state.tab;
+            ui::render_tabs(ui, &mut self.state, &self.localizer);
+            ui.add_space(10.0);
 
-            let url_response = ui::render_url_input(ui, &mut self.state, &self.localizer);
-            if url_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                self.start_download(ctx);
+            if self.state.tab == Tab::Archive && previous_tab != Tab::Archive {
+                self.reload_archive();
             }
 
-            ui.add_space(10.0);
-            ui::render_format_selector(ui, &mut self.state, &self.localizer);
-            ui.add_space(20.0);
+            match self.state.tab {
+                Tab::Downloads => self.render_downloads_tab(ui, ctx),
+                Tab::Subscriptions => self.render_subscriptions_tab(ui),
+                Tab::History => self.render_history_tab(ui),
+                Tab::Archive => self.render_archive_tab(ui),
+            }
+        });
 
-            ui::render_download_dir_selector(ui, &mut self.state, &self.localizer);
-            ui.add_space(20.0);
+        self.persist_jobs();
+        self.persist_subscriptions();
+        self.persist_history();
+        self.persist_settings();
 
-            ui::render_status(ui, &self.state, &self.localizer);
-            ui.add_space(ui.available_height() - 100.0);
+        // Keep polling while work is in flight so progress keeps flowing
+        let fetching = self.state.preview.as_ref().is_some_and(|p| p.loading);
+        if !self.workers.is_empty()
+            || self.state.is_updating
+            || fetching
+            || self.state.queue.has_pending_retry()
+        {
+            ctx.request_repaint_after(std::time::Duration::from_millis(250));
+        } else if !self.state.subscriptions.subscriptions().is_empty() {
+            // Wake up now and then so scheduled checks run while idle
+            ctx.request_repaint_after(std::time::Duration::from_secs(30));
+        }
+    }
 
-            self.render_buttons(ui, ctx);
-        });
+    fn process_events(&mut self, ctx: &egui::Context) {
+        // Checked before draining so every event a worker sent is handled
+        // before its job is released
+        let finished_workers: Vec<JobId> = self
+            .workers
+            .iter()
+            .filter(|(_, worker)| worker.handle.is_finished())
+            .map(|(id, _)| *id)
+            .collect();
+
+        let events: Vec<AppEvent> = match &self.event_receiver {
+            Some(receiver) => receiver.try_iter().collect(),
+            None => Vec::new(),
+        };
+        for event in events {
+            match event {
+                AppEvent::Job(job_id, event) => {
+                    self.handle_job_event(job_id, event);
+                    self.record_history(job_id);
+                }
+                AppEvent::Updater(event) => self.handle_updater_event(event),
+                AppEvent::Info(event) => self.handle_info_event(event, ctx),
+                AppEvent::Subscription(event) => self.handle_subscription_event(event),
+                AppEvent::OpenFailed(message) => {
+                    self.state.error = Some(message.clone());
+                    self.state.last_error = Some(message);
+                }
+            }
+            ctx.request_repaint();
+        }
+
+        for id in finished_workers {
+            self.workers.remove(&id);
+            if let Some(job) = self.state.queue.get_mut(id) {
+                if job.status == JobStatus::Downloading {
+                    job.status = JobStatus::Failed("Download stopped unexpectedly".to_string());
+                }
+            }
+            self.record_history(id);
+            ctx.request_repaint();
+        }
+
+        if self.state.update_pending && !self.ytdlp_in_use() {
+            self.update_ytdlp(ctx);
+        }
+
+        self.start_deferred_fetch();
+        self.check_due_subscriptions();
+
+        // Picks up jobs queued while a download or an update was running
+        self.pump_queue();
     }
 
-    fn process_status_updates(&mut self, ctx: &egui::Context) {
-        if let Some(receiver) = &mut self.status_receiver {
-            while let Ok((is_error, message)) = receiver.try_recv() {
-                // Check if this is a progress update (contains percentage or ETA)
-                if !is_error && (message.contains('%') || message.contains("ETA")) {
-                    println!("Processing progress update: {}", message);
-                    
-                    // Parse the progress update
-                    // Format: [download] 12.3% of 10.00MiB at 1.23MiB/s ETA 00:07
-                    let parts: Vec<&str> = message.split_whitespace().collect();
-                    
-                    // Extract percentage (second part after splitting)
-                    if parts.len() > 1 && parts[1].ends_with('%') {
-                        if let Ok(percent) = parts[1].trim_end_matches('%').parse::<f32>() {
-                            self.state.progress = percent;
-                            
-                            // Extract download speed (after 'at')
-                            if let Some(at_pos) = parts.iter().position(|&x| x == "at") {
-                                if at_pos + 1 < parts.len() {
-                                    self.state.download_speed = parts[at_pos + 1].to_string();
-                                }
-                            }
-                            
-                            // Extract ETA (after 'ETA')
-                            if let Some(eta_pos) = parts.iter().position(|&x| x == "ETA") {
-                                if eta_pos + 1 < parts.len() {
-                                    self.state.eta = parts[eta_pos + 1].to_string();
-                                }
-                            }
-                            
-                            // Update status with the latest progress
-                            self.state.status = if !self.state.download_speed.is_empty() && !self.state.eta.is_empty() {
-                                format!("Downloading: {:.1}% - {} - ETA: {}", 
-                                    percent, 
-                                    self.state.download_speed, 
-                                    self.state.eta
-                                )
-                            } else if !self.state.download_speed.is_empty() {
-                                format!("Downloading: {:.1}% - {}", percent, self.state.download_speed)
-                            } else {
-                                format!("Downloading: {:.1}%", percent)
-                            };
-                            
-                            println!("Updated progress: {}", self.state.status);
-                            ctx.request_repaint();
-                            continue;
-                        }
+    fn handle_job_event(&mut self, job_id: JobId, event: DownloadEvent) {
+        let Some(job) = self.state.queue.get_mut(job_id) else {
+            return;
+        };
+
+        match event {
+            DownloadEvent::Started => {
+                job.status = JobStatus::Downloading;
+            }
+            DownloadEvent::Progress {
+                percent,
+                downloaded_bytes,
+                total_bytes,
+                total_is_estimate,
+                speed,
+                eta,
+                fragments,
+            } => {
+                if let Some(percent) = percent {
+                    job.stage_progress = percent;
+                    let stage = job.stage.unwrap_or(Stage::Downloading);
+                    job.set_item_progress(overall_progress(stage, percent, job.format));
+                }
+                job.downloaded_bytes = downloaded_bytes;
+                job.total_bytes = total_bytes;
+                job.total_is_estimate = total_is_estimate;
+                job.speed = speed;
+                job.eta = eta;
+                job.fragments = fragments;
+            }
+            DownloadEvent::StageChanged(stage) => {
+                job.stage = Some(stage);
+                job.stage_progress = 0.0;
+                job.set_item_progress(overall_progress(stage, 0.0, job.format));
+            }
+            DownloadEvent::ItemChanged { index, count } => {
+                job.item = Some((index, count));
+                job.stage = None;
+                job.stage_progress = 0.0;
+                job.set_item_progress(0.0);
+            }
+            DownloadEvent::Destination(path) => {
+                if !job.destinations.contains(&path) {
+                    job.destinations.push(path);
+                }
+            }
+            DownloadEvent::Intermediate(path) => {
+                if !job.intermediates.contains(&path) {
+                    job.intermediates.push(path);
+                }
+            }
+            DownloadEvent::ExistingFile { path, replaced } => {
+                job.collisions.push(if replaced {
+                    Collision::Overwritten(path)
+                } else {
+                    Collision::AlreadyPresent(path)
+                });
+            }
+            // Already listed as a collision through ExistingFile
+            DownloadEvent::NameTaken { id, path } => job.taken.push((id, path)),
+            DownloadEvent::Warning(message) => {
+                if !job.warnings.contains(&message) {
+                    job.warnings.push(message);
+                }
+            }
+            DownloadEvent::Error { kind, message } => {
+                job.error_kind = Some(kind);
+                job.clear_transfer_stats();
+
+                if kind == ErrorKind::ExtractorOutdated && !job.updated_for_retry {
+                    // A broken extractor is usually fixed upstream already
+                    log::info!("Updating yt-dlp to retry job {}: {}", job_id, message);
+                    job.updated_for_retry = true;
+                    job.status = JobStatus::Queued;
+                    self.state.update_pending = true;
+                    return;
+                }
+
+                // Only failures count against the policy, so pausing, resuming or
+                // renaming a job never uses up one of its attempts
+                job.attempts += 1;
+                let delay = if kind.is_transient() {
+                    self.state.settings.retry.next_delay(job.attempts)
+                } else {
+                    None
+                };
+                match delay {
+                    Some(delay) => {
+                        log::info!("Retrying job {} in {:?}: {}", job_id, delay, message);
+                        job.status = JobStatus::Queued;
+                        job.retry_at = Some(Instant::now() + delay);
+                        job.last_failure = Some(message);
                     }
+                    None => job.status = JobStatus::Failed(message),
+                }
+            }
+            DownloadEvent::Finished { outputs } => {
+                // A renamed run adds to the files of the first one
+                job.output_paths.extend(outputs.iter().map(|output| output.filepath.clone()));
+                if let Some(output) = outputs.into_iter().last() {
+                    job.output_path = Some(output.filepath);
+                    job.title = job.title.take().or(output.title);
+                    job.extractor = output.extractor_key.or(job.extractor.take());
+                    job.media_id = output.id.or(job.media_id.take());
+                    job.duration = output.duration.or(job.duration);
                 }
 
-                // Handle non-progress messages
-                if is_error {
-                    self.state.error = Some(message.clone());
-                    self.state.last_error = Some(message);
-                    self.state.is_downloading = false;
-                } else if message == "Download complete" || message.contains("yt-dlp updated") {
-                    if message.contains("yt-dlp updated") {
-                        self.state.status = self.localizer
-                            .lookup_single_language("update-complete", None)
-                            .unwrap_or_else(|| "Update complete".to_string());
-                    } else {
-                        self.state.status = self.localizer
-                            .lookup_single_language("download-complete", None)
-                            .unwrap_or_else(|| "Download complete".to_string());
+                // Items left out for a taken name run again under a free one.
+                // A renamed run that still meets taken names keeps them
+                let rename = job.collision_policy == CollisionPolicy::Rename
+                    && job.output_name.is_none()
+                    && !job.taken.is_empty();
+                if rename {
+                    let taken = std::mem::take(&mut job.taken);
+                    job.output_name = Some(match (taken.as_slice(), job.item) {
+                        ([(id, existing)], None) => template::renamed_template(existing, Some(id)),
+                        // A literal name can't be given to every item of a
+                        // playlist, so their ids keep the new names apart
+                        _ => template::with_id_suffix(&job.output_template),
+                    });
+                    job.only_ids = taken.iter().map(|(id, _)| id.clone()).collect();
+                    for collision in &mut job.collisions {
+                        if let Collision::AlreadyPresent(existing) = collision {
+                            if taken.iter().any(|(_, path)| path == existing) {
+                                *collision = Collision::Renamed(existing.clone());
+                            }
+                        }
                     }
-                    self.state.progress = 100.0;
-                    self.state.is_downloading = false;
-                    self.state.download_speed.clear();
-                    self.state.eta.clear();
-                } else if !message.trim().is_empty() {
-                    // Only update status for non-empty messages that aren't progress updates
-                    self.state.status = message;
+                    job.status = JobStatus::Queued;
+                    job.progress = 0.0;
+                    job.item = None;
+                    job.clear_transfer_stats();
+                    return;
                 }
+                job.status = JobStatus::Completed;
+                job.progress = 100.0;
+                job.clear_transfer_stats();
+            }
+            DownloadEvent::Paused => {
+                job.status = JobStatus::Paused;
+                job.clear_transfer_stats();
+            }
+            DownloadEvent::Cancelled => {
+                job.status = JobStatus::Cancelled;
+                job.clear_transfer_stats();
+            }
+        }
+    }
+
+    fn handle_updater_event(&mut self, event: UpdaterEvent) {
+        match event {
+            UpdaterEvent::Progress {
+                downloaded_bytes,
+                total_bytes,
+            } => {
+                self.state.updater_progress = Some((downloaded_bytes, total_bytes));
+                return;
+            }
+            UpdaterEvent::Finished => {
+                self.state.status = self
+                    .localizer
+                    .lookup_single_language("update-complete", None)
+                    .unwrap_or_else(|| "Update complete".to_string());
+            }
+            UpdaterEvent::Failed(message) => {
+                self.state.error = Some(message.clone());
+                self.state.last_error = Some(message);
+            }
+        }
+        self.state.is_updating = false;
+        self.state.updater_progress = None;
+    }
+
+    fn render_downloads_tab(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        if let Some(action) = ui::render_recovery_prompt(ui, &self.state, &self.localizer) {
+            self.handle_recovery(action);
+        }
+
+        let url_response = ui::render_url_input(ui, &mut self.state, &self.localizer);
+        if url_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            self.start_download(ctx);
+        }
+
+        ui.add_space(10.0);
+        match ui::render_preview(ui, &mut self.state, &self.localizer) {
+            Some(PreviewAction::Fetch) => self.fetch_info(ctx),
+            Some(PreviewAction::Enqueue) => self.start_download(ctx),
+            None => {}
+        }
+        ui::render_format_chooser(ctx, &mut self.state, &self.localizer);
+
+        ui.add_space(10.0);
+        ui::render_format_selector(ui, &mut self.state, &self.localizer);
+        ui.add_space(20.0);
+
+        ui::render_download_dir_selector(ui, &mut self.state, &self.localizer);
+        ui.add_space(20.0);
+
+        self.render_buttons(ui, ctx);
+        ui.add_space(10.0);
+        ui::render_queue_settings(ui, &mut self.state, &self.localizer);
+        ui::render_template_editor(ui, &mut self.state, &self.localizer);
+        ui::render_subtitle_options(ui, &mut self.state, &self.localizer);
+        if ui::render_embed_options(ui, &mut self.state, &self.localizer) {
+            self.state.ffmpeg_available = ffmpeg_available();
+        }
+
+        ui::render_message(ui, &self.state, &self.localizer);
+
+        if let Some(action) = ui::render_job_list(ui, &self.state.queue, &self.localizer) {
+            match action {
+                JobAction::Cancel(id) => self.cancel_job(id),
+                JobAction::Pause(id) => self.pause_job(id),
+                JobAction::Resume(id) => self.resume_job(id),
+                JobAction::Retry(id) => self.retry_job(id),
+                JobAction::Remedy(id, remedy) => self.apply_remedy(id, remedy, ctx),
+                JobAction::Open(id) => self.open_output(id, false),
+                JobAction::Reveal(id) => self.open_output(id, true),
+                JobAction::Remove(id) => self.state.queue.remove(id),
+                JobAction::ClearFinished => self.state.queue.clear_finished(),
+            }
+        }
+    }
 
-                ctx.request_repaint();
+    fn render_subscriptions_tab(&mut self, ui: &mut egui::Ui) {
+        if let Some(action) = ui::render_subscriptions(ui, &mut self.state, &self.localizer) {
+            match action {
+                SubscriptionAction::Add => self.add_subscription(),
+                SubscriptionAction::Check(id) => self.check_subscription(id),
+                SubscriptionAction::Remove(id) => self.state.subscriptions.remove(id),
             }
         }
     }
 
+    fn render_history_tab(&mut self, ui: &mut egui::Ui) {
+        let Some(action) = ui::render_history(ui, &mut self.state, &self.localizer) else {
+            return;
+        };
+        match action {
+            HistoryAction::Rerun(id) => self.rerun_history_entry(id, false),
+            HistoryAction::Edit(id) => self.rerun_history_entry(id, true),
+            HistoryAction::Delete(id) => self.state.history.remove(id),
+            HistoryAction::Open(id) => self.open_history_output(id, false),
+            HistoryAction::Reveal(id) => self.open_history_output(id, true),
+            HistoryAction::ExportJson => self.export_records("json", records::export_json),
+            HistoryAction::ExportCsv => self.export_records("csv", records::export_csv),
+            HistoryAction::Import => self.import_records(),
+        }
+    }
+
+    fn export_records(
+        &mut self,
+        extension: &str,
+        export: fn(&Path, &[HistoryEntry]) -> Result<(), String>,
+    ) {
+        let Some(path) = FileDialog::new()
+            .add_filter(extension, &[extension])
+            .set_file_name(&format!("ytdl-gui-records.{}", extension))
+            .save_file()
+        else {
+            return;
+        };
+
+        self.state.history_message = Some(match export(&path, self.state.history.entries()) {
+            Ok(()) => format!(
+                "{} {}",
+                self.localizer
+                    .lookup_single_language("records-exported", None)
+                    .unwrap_or_else(|| "Records exported to".to_string()),
+                path.display()
+            ),
+            Err(e) => format!("Error: {}", e),
+        });
+    }
+
+    // Rebuilds history entries from a JSON export and records its completed
+    // downloads in the archive so they are not downloaded again here
+    fn import_records(&mut self) {
+        let Some(path) = FileDialog::new().add_filter("json", &["json"]).pick_file() else {
+            return;
+        };
+
+        let result = records::import_json(&path).and_then(|records| {
+            let entries = records
+                .iter()
+                .map(Record::to_history_entry)
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let archive = load_archive();
+            let keys: Vec<String> = entries
+                .iter()
+                .filter(|entry| entry.outcome == Outcome::Completed)
+                .filter_map(|entry| Some(archive_key(entry.extractor.as_ref()?, entry.media_id.as_ref()?)))
+                .filter(|key| !archive.contains(key))
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+            append_to_archive(&keys)?;
+
+            Ok(self.state.history.import(entries))
+        });
+
+        self.state.history_message = Some(match result {
+            Ok(added) => format!(
+                "{} {}",
+                self.localizer
+                    .lookup_single_language("records-imported", None)
+                    .unwrap_or_else(|| "Records imported:".to_string()),
+                added
+            ),
+            Err(e) => format!("Error: {}", e),
+        });
+    }
+
+    // Downloads a history entry again. With `edit` the entry is loaded into the
+    // Downloads tab instead so format and directory can be changed first
+    fn rerun_history_entry(&mut self, id: HistoryId, edit: bool) {
+        let Some(entry) = self.state.history.get(id).cloned() else {
+            return;
+        };
+
+        // Re-running is deliberate, so the job does not consult the archive.
+        // The video stays listed in it, as it is downloaded again
+        if edit {
+            self.state.rerun_url = Some(entry.url.clone());
+            self.state.url = entry.url;
+            self.state.format = entry.format;
+            self.state.download_dir = entry.download_dir;
+            self.state.preview = None;
+            self.state.tab = Tab::Downloads;
+            return;
+        }
+
+        let job_id = self
+            .state
+            .queue
+            .enqueue(entry.url, entry.format, entry.download_dir);
+        if let Some(job) = self.state.queue.get_mut(job_id) {
+            job.title = entry.title;
+            job.extractor = entry.extractor;
+            job.media_id = entry.media_id;
+            job.duration = entry.duration;
+            job.format_spec = entry.format_spec;
+            job.ignore_archive = true;
+            job.capture_settings(&self.state.settings);
+        }
+        self.state.status = self
+            .localizer
+            .lookup_single_language("job-added", None)
+            .unwrap_or_else(|| "Added to queue".to_string());
+        self.pump_queue();
+    }
+
+    fn open_history_output(&mut self, id: HistoryId, reveal: bool) {
+        if let Some(path) = self.state.history.get(id).and_then(|entry| entry.output_path.clone()) {
+            self.open_file(&path, reveal);
+        }
+    }
+
+    fn render_archive_tab(&mut self, ui: &mut egui::Ui) {
+        self.state.archive.locked = !self.workers.is_empty();
+        let Some(action) = ui::render_archive(ui, &mut self.state, &self.localizer) else {
+            return;
+        };
+
+        let result = match action {
+            ArchiveAction::Remove(key) => remove_from_archive(&[key]).map(|_| None),
+            ArchiveAction::Import => match FileDialog::new().add_filter("Archive", &["txt"]).pick_file() {
+                Some(path) => import_archive(&path).map(|added| {
+                    Some(format!(
+                        "{} {}",
+                        self.localizer
+                            .lookup_single_language("archive-imported", None)
+                            .unwrap_or_else(|| "Entries imported:".to_string()),
+                        added
+                    ))
+                }),
+                None => Ok(None),
+            },
+            ArchiveAction::Export => match FileDialog::new()
+                .set_file_name("archive.txt")
+                .save_file()
+            {
+                Some(path) => export_archive(&path).map(|_| {
+                    Some(format!(
+                        "{} {}",
+                        self.localizer
+                            .lookup_single_language("archive-exported", None)
+                            .unwrap_or_else(|| "Archive exported to".to_string()),
+                        path.display()
+                    ))
+                }),
+                None => Ok(None),
+            },
+            ArchiveAction::Reload => Ok(None),
+        };
+
+        self.reload_archive();
+        self.state.archive.message = match result {
+            Ok(message) => message,
+            Err(e) => Some(format!("Error: {}", e)),
+        };
+    }
+
+    fn reload_archive(&mut self) {
+        self.state.archive.entries = load_entries();
+        self.state.archive.message = None;
+    }
+
     fn render_buttons(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
         ui.horizontal(|ui| {
             ui.add_space(ui.available_width() / 2.0 - 150.0);
@@ -265,4 +1323,28 @@ impl eframe::App for YtdlApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         self.update_ui(ctx);
     }
+
+    // Running jobs stay in JOBS_FILE; stopping yt-dlp here keeps their
+    // partial files consistent for the next start. Workers get a moment to
+    // see their process group exit so no yt-dlp or ffmpeg outlives the app
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        for worker in self.workers.values() {
+            worker.control.request_stop(StopRequest::Pause);
+        }
+        let deadline = Instant::now() + EXIT_TIMEOUT;
+        while Instant::now() < deadline && self.workers.values().any(|w| !w.handle.is_finished()) {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        // What they reported on the way out, e.g. late destinations, is saved too
+        let events: Vec<AppEvent> = match &self.event_receiver {
+            Some(receiver) => receiver.try_iter().collect(),
+            None => Vec::new(),
+        };
+        for event in events {
+            if let AppEvent::Job(job_id, event) = event {
+                self.handle_job_event(job_id, event);
+            }
+        }
+        self.persist_jobs();
+    }
 }
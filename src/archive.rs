@@ -0,0 +1,194 @@
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::storage;
+
+// yt-dlp's download archive: one `<extractor> <video id>` line per finished
+// download, written by yt-dlp to a per-run copy passed as
+// `--download-archive` and merged back when the run ends
+const ARCHIVE_FILE: &str = "archive.txt";
+
+pub fn archive_path() -> PathBuf {
+    storage::config_dir().join(ARCHIVE_FILE)
+}
+
+// Key yt-dlp writes for a video; the extractor name is lowercased
+pub fn archive_key(extractor: &str, id: &str) -> String {
+    format!("{} {}", extractor.to_lowercase(), id)
+}
+
+fn read_entries(path: &Path) -> Result<Vec<String>, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+// Archive lines in file order. A missing archive is simply empty
+pub fn load_entries() -> Vec<String> {
+    read_entries(&archive_path()).unwrap_or_default()
+}
+
+pub fn load_archive() -> HashSet<String> {
+    load_entries().into_iter().collect()
+}
+
+fn append_entries(path: &Path, keys: &[String]) -> Result<(), String> {
+    if keys.is_empty() {
+        return Ok(());
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| e.to_string())?;
+    for key in keys {
+        writeln!(file, "{}", key).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+// Records videos as downloaded without downloading them
+pub fn append_to_archive(keys: &[String]) -> Result<(), String> {
+    append_entries(&archive_path(), keys)
+}
+
+// yt-dlp also lists items it skipped because their file already existed,
+// so a run is given a copy of the archive and only what it really
+// downloaded is merged back. A missing archive gives an empty copy
+pub fn copy_for_run(archive: &Path, copy: &Path) -> Result<(), String> {
+    match fs::copy(archive, copy) {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => fs::write(copy, "").map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// Appends what a run added to its copy, except the `left_out` keys, to the
+// archive. Other runs may have appended to it meanwhile, so entries are
+// compared with the archive as it is now. Returns how many were added
+pub fn merge_run_archive(archive: &Path, copy: &Path, left_out: &[String]) -> Result<usize, String> {
+    let existing: HashSet<String> = read_entries(archive)
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    let mut seen = HashSet::new();
+    let new_entries: Vec<String> = read_entries(copy)?
+        .into_iter()
+        .filter(|entry| !existing.contains(entry) && !left_out.contains(entry) && seen.insert(entry.clone()))
+        .collect();
+    append_entries(archive, &new_entries)?;
+    Ok(new_entries.len())
+}
+
+// Rewrites an archive through a temporary file, like the JSON state files
+fn write_entries(path: &Path, entries: &[String]) -> Result<(), String> {
+    let tmp_path = path.with_extension("txt.tmp");
+    let mut content = entries.join("\n");
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    fs::write(&tmp_path, content).map_err(|e| e.to_string())?;
+    fs::rename(&tmp_path, path).map_err(|e| e.to_string())
+}
+
+fn remove_entries(archive: &Path, keys: &[String]) -> Result<(), String> {
+    let entries: Vec<String> = read_entries(archive)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|entry| !keys.contains(entry))
+        .collect();
+    write_entries(archive, &entries)
+}
+
+// Forgets the given videos so they can be downloaded again
+pub fn remove_from_archive(keys: &[String]) -> Result<(), String> {
+    remove_entries(&archive_path(), keys)
+}
+
+fn import_entries(archive: &Path, path: &Path) -> Result<usize, String> {
+    let existing: HashSet<String> = read_entries(archive)
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    let mut seen = HashSet::new();
+    let new_entries: Vec<String> = read_entries(path)?
+        .into_iter()
+        .filter(|entry| entry.contains(' ') && !existing.contains(entry) && seen.insert(entry.clone()))
+        .collect();
+    append_entries(archive, &new_entries)?;
+    Ok(new_entries.len())
+}
+
+// Merges another archive file into ours, skipping lines we already have.
+// Returns how many entries were added
+pub fn import_archive(path: &Path) -> Result<usize, String> {
+    import_entries(&archive_path(), path)
+}
+
+pub fn export_archive(path: &Path) -> Result<(), String> {
+    let mut content = load_entries().join("\n");
+    content.push('\n');
+    fs::write(path, content).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("archive-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn keys_use_the_lowercase_extractor() {
+        assert_eq!(archive_key("Youtube", "dQw4w9WgXcQ"), "youtube dQw4w9WgXcQ");
+    }
+
+    #[test]
+    fn removes_only_the_given_entries() {
+        let dir = temp_dir("remove");
+        let archive = dir.join("archive.txt");
+        fs::write(&archive, "youtube a\nvimeo b\nyoutube c\n").unwrap();
+
+        remove_entries(&archive, &["vimeo b".to_string()]).unwrap();
+        assert_eq!(read_entries(&archive).unwrap(), ["youtube a", "youtube c"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn import_skips_known_repeated_and_malformed_lines() {
+        let dir = temp_dir("import");
+        let archive = dir.join("archive.txt");
+        let other = dir.join("other.txt");
+        fs::write(&archive, "youtube a\n").unwrap();
+        fs::write(&other, "youtube a\nyoutube b\n\nyoutube b\nnonsense\n").unwrap();
+
+        assert_eq!(import_entries(&archive, &other).unwrap(), 1);
+        assert_eq!(read_entries(&archive).unwrap(), ["youtube a", "youtube b"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn merges_only_what_the_run_downloaded() {
+        let dir = temp_dir("merge");
+        let archive = dir.join("archive.txt");
+        let copy = dir.join("run.txt");
+
+        copy_for_run(&archive, &copy).unwrap();
+        fs::write(&archive, "youtube a\n").unwrap();
+        fs::write(&copy, "youtube a\nyoutube b\nyoutube taken\nyoutube b\n").unwrap();
+        let added = merge_run_archive(&archive, &copy, &["youtube taken".to_string()]).unwrap();
+
+        assert_eq!(added, 1);
+        assert_eq!(read_entries(&archive).unwrap(), ["youtube a", "youtube b"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
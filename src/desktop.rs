@@ -0,0 +1,63 @@
+use std::path::Path;
+use std::process::Command;
+use std::sync::mpsc::Sender;
+use std::thread;
+
+use crate::events::AppEvent;
+
+// Opens a file in the user's default application. xdg-open is waited for on
+// its own thread so it does not linger as a zombie once it exits
+pub fn open_path(path: &Path) -> Result<(), String> {
+    let mut child = Command::new("xdg-open")
+        .arg(path)
+        .spawn()
+        .map_err(|e| format!("Failed to run xdg-open: {}", e))?;
+    thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+// Shows a file selected in the file manager through the FileManager1 D-Bus
+// interface, falling back to opening its directory when no file manager
+// implements it. dbus-send blocks until it gets a reply, so this runs on its
+// own thread and reports a failure as AppEvent::OpenFailed
+pub fn reveal_in_file_manager(path: &Path, tx: Sender<AppEvent>) {
+    let path = path.to_path_buf();
+    thread::spawn(move || {
+        let shown = Command::new("dbus-send")
+            .arg("--session")
+            .arg("--print-reply")
+            .arg("--dest=org.freedesktop.FileManager1")
+            .arg("--type=method_call")
+            .arg("/org/freedesktop/FileManager1")
+            .arg("org.freedesktop.FileManager1.ShowItems")
+            .arg(format!("array:string:{}", file_uri(&path)))
+            .arg("string:")
+            .stdout(std::process::Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false);
+
+        if shown {
+            return;
+        }
+
+        if let Err(e) = open_path(path.parent().unwrap_or(&path)) {
+            let _ = tx.send(AppEvent::OpenFailed(e));
+        }
+    });
+}
+
+// `file://` URI with everything outside RFC 3986's unreserved set escaped
+fn file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri
+}
@@ -1,27 +1,45 @@
 use std::fs;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus};
 use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
 use std::thread;
-use std::io::Write;
+use std::time::Duration;
+use std::io::{Read, Write};
 use reqwest::blocking::get;
 use std::os::unix::fs::PermissionsExt;
+use std::os::unix::process::CommandExt;
 
-use crate::models::DownloadFormat;
+use crate::errors::{classify_failure, ErrorKind};
+use crate::events::{AppEvent, DownloadEvent, InfoEvent, Stage, SubscriptionEvent, UpdaterEvent};
+use crate::models::{
+    CollisionPolicy, DownloadFormat, DownloadOptions, DownloadedFile, JobId, PlaylistInfo,
+    VideoMetadata,
+};
+use crate::progress::{
+    parse_item_line, parse_progress_line, postprocessor_stage, PROGRESS_TEMPLATE,
+};
+use crate::archive::{self, archive_key};
+use crate::storage;
+use crate::subscriptions::SubscriptionId;
+use crate::template::{self, DEFAULT_OUTPUT_TEMPLATE};
 
 const YT_DLP_BINARY: &str = "yt-dlp";
 const YT_DLP_URL: &str = "https://github.com/yt-dlp/yt-dlp/releases/latest/download/yt-dlp";
 
+// Parallel jobs share one managed binary, so only one of them may check or
+// replace it at a time
+static YTDLP_LOCK: Mutex<()> = Mutex::new(());
+
 fn get_local_ytdlp_path() -> std::path::PathBuf {
-    let mut path = dirs::config_dir().unwrap_or_else(|| "./".into());
-    path.push("ytdl-gui");
-    if !path.exists() {
-        let _ = fs::create_dir_all(&path);
-    }
-    path.push(YT_DLP_BINARY);
-    path
+    storage::config_dir().join(YT_DLP_BINARY)
 }
 
-fn ensure_ytdlp_exists() -> Result<String, String> {
+// Called with the bytes received so far and the total size, when known
+type FetchProgress<'a> = &'a dyn Fn(u64, Option<u64>);
+
+fn ensure_ytdlp_exists(on_progress: FetchProgress) -> Result<String, String> {
+    let _guard = YTDLP_LOCK.lock().unwrap_or_else(|e| e.into_inner());
     let local_path = get_local_ytdlp_path();
     
     // If binary doesn't exist or is older than 7 days, download it
@@ -37,7 +55,7 @@ fn ensure_ytdlp_exists() -> Result<String, String> {
     };
 
     if needs_download {
-        download_ytdlp(&local_path)?;
+        download_ytdlp(&local_path, on_progress)?;
     } else {
         // Make sure it's executable
         let perms = fs::metadata(&local_path)
@@ -53,15 +71,25 @@ fn ensure_ytdlp_exists() -> Result<String, String> {
     local_path.to_str().ok_or_else(|| "Invalid path".to_string()).map(|s| s.to_string())
 }
 
-fn download_ytdlp(path: &std::path::Path) -> Result<(), String> {
-    let response = get(YT_DLP_URL).map_err(|e| e.to_string())?;
+fn download_ytdlp(path: &std::path::Path, on_progress: FetchProgress) -> Result<(), String> {
+    let mut response = get(YT_DLP_URL).map_err(|e| e.to_string())?;
     if !response.status().is_success() {
         return Err(format!("Failed to download yt-dlp: {}", response.status()));
     }
     
+    let total = response.content_length();
     let mut file = fs::File::create(path).map_err(|e| e.to_string())?;
-    let content = response.bytes().map_err(|e| e.to_string())?;
-    file.write_all(&content).map_err(|e| e.to_string())?;
+    let mut buffer = [0u8; 64 * 1024];
+    let mut downloaded = 0u64;
+    loop {
+        let read = response.read(&mut buffer).map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        file.write_all(&buffer[..read]).map_err(|e| e.to_string())?;
+        downloaded += read as u64;
+        on_progress(downloaded, total);
+    }
     
     // Make it executable
     let mut perms = fs::metadata(path).map_err(|e| e.to_string())?.permissions();
@@ -71,61 +99,459 @@ fn download_ytdlp(path: &std::path::Path) -> Result<(), String> {
     Ok(())
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopRequest {
+    Cancel { delete_partial: bool },
+    // Stops yt-dlp but keeps `.part` files so `--continue` can resume them
+    Pause,
+}
+
+// Shared between the UI and a download worker so the UI can stop the
+// yt-dlp process the worker spawned
+#[derive(Clone, Default)]
+pub struct DownloadControl {
+    process_group: Arc<Mutex<Option<i32>>>,
+    stop: Arc<Mutex<Option<StopRequest>>>,
+}
+
+impl DownloadControl {
+    pub fn request_stop(&self, request: StopRequest) {
+        *self.stop.lock().unwrap_or_else(|e| e.into_inner()) = Some(request);
+        if let Some(pgid) = *self.process_group.lock().unwrap_or_else(|e| e.into_inner()) {
+            // yt-dlp leads its own process group, so this also reaches ffmpeg
+            unsafe {
+                libc::kill(-pgid, libc::SIGTERM);
+            }
+        }
+    }
+
+    fn stop_request(&self) -> Option<StopRequest> {
+        *self.stop.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn set_process_group(&self, pgid: Option<i32>) {
+        *self.process_group.lock().unwrap_or_else(|e| e.into_inner()) = pgid;
+    }
+
+    // Reaps the child while holding the process group lock, so a stop can
+    // never signal a group ID the kernel has already handed to someone else
+    fn wait(&self, child: &mut Child) -> std::io::Result<ExitStatus> {
+        loop {
+            {
+                let mut process_group = self.process_group.lock().unwrap_or_else(|e| e.into_inner());
+                if let Some(status) = child.try_wait()? {
+                    *process_group = None;
+                    return Ok(status);
+                }
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+}
+
+// Removes what an interrupted download leaves behind: `.part`, `.ytdl` and
+// fragment files, half-merged `.temp` and half-tagged chapter files, and the
+// per-format streams yt-dlp reported it would merge
+pub fn cleanup_partial_files(destinations: &[PathBuf], intermediates: &[PathBuf]) {
+    for intermediate in intermediates {
+        let _ = fs::remove_file(intermediate);
+    }
+    for destination in destinations {
+        let Some(name) = destination.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let dir = destination.parent().unwrap_or_else(|| Path::new("."));
+
+        let _ = fs::remove_file(dir.join(format!("{}.part", name)));
+        let _ = fs::remove_file(dir.join(format!("{}.ytdl", name)));
+        let _ = fs::remove_file(marked_path(destination, "temp"));
+        let _ = fs::remove_file(marked_path(destination, "tagging"));
+
+        let fragment_prefix = format!("{}.part-Frag", name);
+        if let Ok(entries) = fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                if entry.file_name().to_string_lossy().starts_with(&fragment_prefix) {
+                    let _ = fs::remove_file(entry.path());
+                }
+            }
+        }
+    }
+}
+
+enum StderrLine {
+    Warning(String),
+    Error(String),
+    // Tracebacks, verbose output and anything else without a severity prefix
+    Debug(String),
+}
+
+fn classify_stderr_line(line: &str) -> Option<StderrLine> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+
+    Some(if let Some(warning) = line.strip_prefix("WARNING:") {
+        StderrLine::Warning(warning.trim().to_string())
+    } else if let Some(error) = line.strip_prefix("ERROR:") {
+        StderrLine::Error(error.trim().to_string())
+    } else {
+        StderrLine::Debug(line.to_string())
+    })
+}
+
+// yt-dlp reports an output file it found with "[download] <path> has
+// already been downloaded", "[ExtractAudio] Post-process file <path>
+// exists, skipping", or "Deleting existing file <path>" when told to
+// overwrite it
+fn existing_file_line(line: &str) -> Option<(&str, bool)> {
+    if let Some(path) = line.strip_prefix("Deleting existing file ") {
+        return Some((path.trim(), true));
+    }
+    if let Some(path) = line.strip_prefix("[ExtractAudio] Post-process file ") {
+        return Some((path.strip_suffix(" exists, skipping")?.trim(), false));
+    }
+    let path = line.strip_prefix("[download] ")?;
+    let path = path
+        .strip_suffix(" has already been downloaded")
+        .or_else(|| path.strip_suffix(" has already been downloaded and merged"))?;
+    Some((path.trim(), false))
+}
+
+// Whether `existing` is the file `output` would have been. An MP3 whose
+// extraction was skipped reports the source it would have been made from
+fn is_output_of(existing: &Path, output: &DownloadedFile, format: DownloadFormat) -> bool {
+    same_file(existing, &output.filepath)
+        || (format == DownloadFormat::MP3 && same_file(existing, &output.filepath.with_extension("mp3")))
+}
+
+// Paths from yt-dlp's output and from its JSON may be spelled differently
+fn same_file(a: &Path, b: &Path) -> bool {
+    a == b
+        || matches!(
+            (fs::canonicalize(a), fs::canonicalize(b)),
+            (Ok(a), Ok(b)) if a == b
+        )
+}
+
+// "[SplitChapters] Chapter 001; Destination: <path>"
+fn chapter_destination(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix("[SplitChapters] Chapter ")?;
+    let (_, path) = rest.split_once("; Destination: ")?;
+    Some(path.trim())
+}
+
+// "[info] <id>: Downloading 1 format(s): 137+140". Formats joined with `+`
+// are downloaded to files of their own and merged afterwards
+fn merges_formats(line: &str) -> Option<bool> {
+    let (_, rest) = line.strip_prefix("[info] ")?.split_once(": Downloading ")?;
+    let (_, formats) = rest.split_once(" format(s): ")?;
+    Some(formats.trim().contains('+'))
+}
+
+// "[Merger] Merging formats into "<path>""
+fn merger_output(line: &str) -> Option<&str> {
+    let path = line.strip_prefix("[Merger] Merging formats into ")?.trim();
+    path.strip_prefix('"')?.strip_suffix('"')
+}
+
+// `<stem>.<marker>.<ext>`: how yt-dlp names a file it merges into, and the
+// chapter tagging a file it tags into, before replacing `file` with it
+fn marked_path(file: &Path, marker: &str) -> PathBuf {
+    match file.extension() {
+        Some(ext) => file.with_extension(format!("{}.{}", marker, ext.to_string_lossy())),
+        None => file.with_extension(marker),
+    }
+}
+
+// Chapter files carry the tags of the whole video. Each one is given its
+// chapter title, a track number and the video title as album instead,
+// keeping every other tag and stream. Files are matched to the item they
+// were split from, and ffmpeg runs in the job's process group so the job
+// can still be stopped; the caller checks for that afterwards
+fn tag_chapter_files(
+    outputs: &[DownloadedFile],
+    chapter_files: &[(Option<u64>, PathBuf)],
+    control: &DownloadControl,
+    send: &dyn Fn(DownloadEvent),
+) -> Result<(), String> {
+    let mut tagged_count = 0;
+    for output in outputs {
+        let files: Vec<&PathBuf> = chapter_files
+            .iter()
+            .filter(|(item, _)| *item == output.playlist_autonumber)
+            .map(|(_, file)| file)
+            .collect();
+        for (index, file) in files.iter().enumerate() {
+            if control.stop_request().is_some() {
+                return Ok(());
+            }
+            let tagged = marked_path(file, "tagging");
+
+            let mut cmd = Command::new("ffmpeg");
+            cmd.arg("-y")
+               .arg("-loglevel")
+               .arg("error")
+               .arg("-i")
+               .arg(file)
+               .args(["-map", "0", "-dn", "-ignore_unknown", "-c", "copy"])
+               .arg("-metadata")
+               .arg(format!("track={}/{}", index + 1, files.len()));
+            if let Some(title) = output.chapters.get(index).and_then(|c| c.title.as_ref()) {
+                cmd.arg("-metadata").arg(format!("title={}", title));
+            }
+            if let Some(album) = &output.title {
+                cmd.arg("-metadata").arg(format!("album={}", album));
+            }
+            cmd.arg(&tagged);
+
+            let mut child = cmd
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::piped())
+                .process_group(0)
+                .spawn()
+                .map_err(|e| format!("Failed to start ffmpeg: {}", e))?;
+            control.set_process_group(Some(child.id() as i32));
+            if let Some(request) = control.stop_request() {
+                control.request_stop(request);
+            }
+            let mut stderr = String::new();
+            if let Some(mut pipe) = child.stderr.take() {
+                let _ = pipe.read_to_string(&mut stderr);
+            }
+            let status = control
+                .wait(&mut child)
+                .map_err(|e| format!("Failed to wait for ffmpeg: {}", e))?;
+            if control.stop_request().is_some() {
+                let _ = fs::remove_file(&tagged);
+                return Ok(());
+            }
+            if !status.success() {
+                let _ = fs::remove_file(&tagged);
+                return Err(format!(
+                    "Failed to tag {}: {}",
+                    file.display(),
+                    stderr.lines().last().unwrap_or_default()
+                ));
+            }
+            fs::rename(&tagged, file).map_err(|e| format!("Failed to tag {}: {}", file.display(), e))?;
+
+            tagged_count += 1;
+            send(DownloadEvent::Progress {
+                percent: Some(tagged_count as f32 * 100.0 / chapter_files.len() as f32),
+                downloaded_bytes: None,
+                total_bytes: None,
+                total_is_estimate: false,
+                speed: None,
+                eta: None,
+                fragments: None,
+            });
+        }
+    }
+    Ok(())
+}
+
+// Arguments deciding which files a run writes and where
+fn add_output_args(cmd: &mut Command, format: DownloadFormat, download_dir: &str, options: &DownloadOptions) {
+    if matches!(format, DownloadFormat::MP3) {
+        cmd.arg("-x")
+           .arg("--audio-format")
+           .arg("mp3")
+           .arg("--audio-quality")
+           .arg("0");
+        if let Some(spec) = &options.format_spec {
+            cmd.arg("-f").arg(spec);
+        }
+    } else {
+        // yt-dlp falls back to mkv when the picked streams can't be merged into mp4
+        let spec = options
+            .format_spec
+            .as_deref()
+            .unwrap_or("bestvideo[ext=mp4]+bestaudio[ext=m4a]/best[ext=mp4]/best");
+        cmd.arg("-f").arg(spec);
+    }
+
+    if let Some(cookies_file) = &options.cookies_file {
+        cmd.arg("--cookies").arg(cookies_file);
+    }
+    // Set output directory and template
+    cmd.arg("-P")
+       .arg(download_dir)
+       .arg("-o")
+       .arg(options.output_template.as_deref().unwrap_or(DEFAULT_OUTPUT_TEMPLATE));
+}
+
+// `--match-filter`s limiting a run to the `only` items, or all when empty.
+// yt-dlp runs items passing any of the filters
+fn match_filter_args(only: &[String]) -> Vec<String> {
+    only.iter()
+        .flat_map(|id| ["--match-filter".to_string(), format!("id='{}'", id.replace('\'', "\\'"))])
+        .collect()
+}
+
+fn stopped_event(request: StopRequest) -> DownloadEvent {
+    match request {
+        StopRequest::Cancel { .. } => DownloadEvent::Cancelled,
+        StopRequest::Pause => DownloadEvent::Paused,
+    }
+}
+
+// Passed to `--print-to-file`; fields must match DownloadedFile
+const DOWNLOADED_FILE_TEMPLATE: &str =
+    "after_move:%(.{filepath,id,title,extractor_key,duration,chapters,playlist_autonumber})j";
+
 pub fn start_download(
+    job_id: JobId,
     url: String,
     format: DownloadFormat,
     download_dir: String,
-    tx: Sender<(bool, String)>,
+    options: DownloadOptions,
+    control: DownloadControl,
+    tx: Sender<AppEvent>,
 ) -> thread::JoinHandle<()> {
     thread::spawn(move || {
-        let ytdlp_path = match ensure_ytdlp_exists() {
+        // The receiver is gone once the app is closing, so events sent after
+        // that are simply dropped
+        let send = |event: DownloadEvent| {
+            let _ = tx.send(AppEvent::Job(job_id, event));
+        };
+
+        let ytdlp_path = match ensure_ytdlp_exists(&|_, _| {}) {
             Ok(path) => path,
             Err(e) => {
-                let _ = tx.send((true, format!("Failed to get yt-dlp: {}", e)));
+                send(DownloadEvent::Error {
+                    kind: ErrorKind::YtdlpUnavailable,
+                    message: format!("Failed to get yt-dlp: {}", e),
+                });
                 return;
             }
         };
 
+        // Stopped while the managed binary was being fetched
+        if let Some(request) = control.stop_request() {
+            send(stopped_event(request));
+            return;
+        }
+
         let mut cmd = Command::new(&ytdlp_path);
         
         cmd.arg("--newline")
            .arg("--progress")
-           .arg("--no-check-certificate");
-        
-        if matches!(format, DownloadFormat::MP3) {
-            cmd.arg("-x")
-               .arg("--audio-format")
-               .arg("mp3")
-               .arg("--audio-quality")
-               .arg("0");
-        } else {
-            cmd.arg("-f")
-               .arg("bestvideo[ext=mp4]+bestaudio[ext=m4a]/best[ext=mp4]/best");
+           .arg("--no-check-certificate")
+           .arg("--continue");
+        add_output_args(&mut cmd, format, &download_dir, &options);
+        cmd.args(match_filter_args(&options.only_ids));
+
+        // yt-dlp writes to a copy of the archive, merged back once the run
+        // is over without the items whose name was taken
+        let run_archive = options.download_archive.as_ref().map(|archive| {
+            let copy = std::env::temp_dir()
+                .join(format!("ytdl-gui-{}-{}.archive", std::process::id(), job_id));
+            match archive::copy_for_run(archive, &copy) {
+                Ok(()) => (archive.clone(), copy),
+                Err(e) => {
+                    log::warn!("Failed to copy the download archive, using it directly: {}", e);
+                    (archive.clone(), archive.clone())
+                }
+            }
+        });
+        if let Some((_, run_archive)) = &run_archive {
+            cmd.arg("--download-archive").arg(run_archive);
+        }
+        if let Some(subtitles) = &options.subtitles {
+            cmd.arg("--write-subs")
+               .arg("--sub-langs")
+               .arg(subtitles.languages.join(","));
+            if subtitles.automatic {
+                cmd.arg("--write-auto-subs");
+            }
+            // Audio files can't carry subtitles, so MP3 jobs always get sidecar files
+            if subtitles.embed && matches!(format, DownloadFormat::MP4) {
+                cmd.arg("--embed-subs");
+            } else {
+                let ext = subtitles.format.ext();
+                cmd.arg("--sub-format").arg(format!("{}/best", ext));
+                // Without ffmpeg the file is kept as the site offers it
+                if options.convert_subtitles {
+                    cmd.arg("--convert-subs").arg(ext);
+                }
+            }
+        }
+        if options.embed.thumbnail {
+            // JPEG covers show up in every player, WebP ones don't
+            cmd.arg("--embed-thumbnail")
+               .arg("--convert-thumbnails")
+               .arg("jpg");
+        }
+        if options.embed.metadata {
+            cmd.arg("--embed-metadata");
+        }
+        if options.embed.chapters {
+            cmd.arg("--embed-chapters");
+        }
+        if options.split_chapters {
+            cmd.arg("--split-chapters")
+               .arg("-o")
+               .arg(template::chapter_template(
+                   options.output_template.as_deref().unwrap_or(DEFAULT_OUTPUT_TEMPLATE),
+               ));
+        }
+        // Renaming happens in the app once the run is done. yt-dlp still
+        // downloads the source of an MP3 whose name is taken; only the
+        // extraction is skipped
+        match options.collision_policy {
+            CollisionPolicy::Overwrite => {
+                cmd.arg("--force-overwrites");
+            }
+            CollisionPolicy::Skip | CollisionPolicy::Rename => {
+                cmd.arg("--no-force-overwrites").arg("--no-post-overwrites");
+            }
         }
         
-        // Set output directory and template
-        cmd.arg("-P")
-           .arg(&download_dir)
-           .arg("-o")
-           .arg("%(title)s.%(ext)s")
-           .arg("--newline")
+        // yt-dlp appends a JSON line describing every file it finishes, after
+        // merging and moving, to this file
+        let paths_file = std::env::temp_dir()
+            .join(format!("ytdl-gui-{}-{}.paths", std::process::id(), job_id));
+        let _ = fs::remove_file(&paths_file);
+        cmd.arg("--print-to-file")
+           .arg(DOWNLOADED_FILE_TEMPLATE)
+           .arg(&paths_file);
+
+        cmd.arg("--newline")
            .arg("--progress")
            .arg("--console-title")
            .arg("--no-simulate")
            .arg("--progress-template")
-           .arg("[download] %(progress._percent_str)s of %(progress._total_bytes_str)s at %(progress._speed_str)s ETA %(progress._eta_str)s")
+           .arg(PROGRESS_TEMPLATE)
            .arg(&url);
         
-        // Spawn the command with piped output
+        // Spawn the command with piped output in its own process group so
+        // cancelling also stops the ffmpeg processes yt-dlp starts
         let mut child = match cmd.stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
+            .process_group(0)
             .spawn() {
                 Ok(child) => child,
                 Err(e) => {
-                    let _ = tx.send((true, format!("Failed to start yt-dlp: {}", e)));
+                    send(DownloadEvent::Error {
+                        kind: ErrorKind::Launch,
+                        message: format!("Failed to start yt-dlp: {}", e),
+                    });
                     return;
                 }
             };
+        control.set_process_group(Some(child.id() as i32));
+        // A stop requested before the group was known has not signalled anything yet
+        if let Some(request) = control.stop_request() {
+            control.request_stop(request);
+        }
+        send(DownloadEvent::Started);
+
+        let destinations: Arc<Mutex<Vec<PathBuf>>> = Arc::default();
+        let stdout_destinations = Arc::clone(&destinations);
+        let intermediates: Arc<Mutex<Vec<PathBuf>>> = Arc::default();
+        let stdout_intermediates = Arc::clone(&intermediates);
+        let output_dir = PathBuf::from(&download_dir);
         
         // Read stdout and stderr in a separate thread
         let stdout = child.stdout.take().unwrap();
@@ -136,90 +562,446 @@ pub fn start_download(
         // Handle stdout (progress updates)
         let stdout_handle = std::thread::spawn(move || {
             use std::io::{BufRead, BufReader};
+            let send = |event: DownloadEvent| {
+                let _ = tx_stdout.send(AppEvent::Job(job_id, event));
+            };
+            let mut stage = None;
+            let mut item = None;
+            // Whether the current item's streams are merged once downloaded
+            let mut merging = false;
+            let mut chapter_files = Vec::new();
+            // Output files found already there and left alone
+            let mut existing = Vec::new();
+            let set_stage = |stage: &mut Option<Stage>, new_stage: Stage| {
+                if *stage != Some(new_stage) {
+                    *stage = Some(new_stage);
+                    send(DownloadEvent::StageChanged(new_stage));
+                }
+            };
+            // A new item starts its stages over
+            let set_item = |item: &mut Option<(u64, u64)>, stage: &mut Option<Stage>, new_item: (u64, u64)| {
+                if *item != Some(new_item) {
+                    *item = Some(new_item);
+                    *stage = None;
+                    send(DownloadEvent::ItemChanged {
+                        index: new_item.0,
+                        count: new_item.1,
+                    });
+                }
+            };
+
             let reader = BufReader::new(stdout);
             for line in reader.lines() {
                 match line {
                     Ok(line) => {
-                        println!("STDOUT: {}", line);
-                        // Forward all progress-related lines
-                        if line.starts_with("[download]") || line.contains("ETA") {
-                            // Send the update and immediately request a repaint
-                            if let Err(e) = tx_stdout.send((false, line)) {
-                                println!("Failed to send progress update: {}", e);
+                        log::debug!("STDOUT: {}", line);
+                        if let Some(path) = line.strip_prefix("[download] Destination: ") {
+                            let path = output_dir.join(path.trim());
+                            stdout_destinations
+                                .lock()
+                                .unwrap_or_else(|e| e.into_inner())
+                                .push(path.clone());
+                            if merging {
+                                stdout_intermediates
+                                    .lock()
+                                    .unwrap_or_else(|e| e.into_inner())
+                                    .push(path.clone());
+                                send(DownloadEvent::Intermediate(path.clone()));
                             }
+                            send(DownloadEvent::Destination(path));
+                        } else if let Some(merges) = merges_formats(&line) {
+                            merging = merges;
+                        } else if let Some(path) = merger_output(&line) {
+                            // Listed for its `.temp` file, which a cancel leaves behind
+                            let path = output_dir.join(path);
+                            stdout_destinations
+                                .lock()
+                                .unwrap_or_else(|e| e.into_inner())
+                                .push(path.clone());
+                            send(DownloadEvent::Destination(path));
+                            set_stage(&mut stage, Stage::Merging);
+                        } else if let Some(path) = chapter_destination(&line) {
+                            // Kept with the item it was split from, matched to
+                            // that item's output later
+                            let path = output_dir.join(path);
+                            stdout_destinations
+                                .lock()
+                                .unwrap_or_else(|e| e.into_inner())
+                                .push(path.clone());
+                            send(DownloadEvent::Destination(path.clone()));
+                            chapter_files.push((item.map(|(index, _)| index), path));
+                            set_stage(&mut stage, Stage::PostProcessing);
+                        } else if let Some((path, replaced)) = existing_file_line(&line) {
+                            let path = output_dir.join(path);
+                            if !replaced {
+                                existing.push(path.clone());
+                            }
+                            send(DownloadEvent::ExistingFile { path, replaced });
+                        } else if let Some(new_item) = parse_item_line(&line) {
+                            set_item(&mut item, &mut stage, new_item);
+                        } else if let Some(progress) = parse_progress_line(&line) {
+                            // Item and stage go first so the UI places this progress correctly
+                            if let Some(new_item) = progress.item {
+                                set_item(&mut item, &mut stage, new_item);
+                            }
+                            set_stage(&mut stage, progress.stage);
+                            send(progress.event);
+                        } else if let Some(postprocessor) = postprocessor_stage(&line) {
+                            set_stage(&mut stage, postprocessor);
+                        } else if line.ends_with("has already been recorded in the archive") {
+                            // Skipped rather than downloaded, which is worth pointing out
+                            let message = line.trim_start_matches("[download]").trim();
+                            send(DownloadEvent::Warning(message.to_string()));
                         }
                     }
                     Err(e) => {
-                        println!("Error reading from stdout: {}", e);
+                        log::warn!("Error reading from stdout: {}", e);
                     }
                 }
             }
+            (chapter_files, existing)
         });
         
-        // Handle stderr (errors)
+        // Handle stderr. Warnings are forwarded as they come; error lines are
+        // collected and only reported if the process actually fails
         let stderr_handle = std::thread::spawn(move || {
             use std::io::{BufRead, BufReader};
+            let mut error_lines = Vec::new();
+            let mut last_debug_line = None;
             let reader = BufReader::new(stderr);
             for line in reader.lines() {
                 match line {
                     Ok(line) => {
-                        let line = line.trim().to_string();
-                        if !line.is_empty() {
-                            println!("STDERR: {}", line);
-                            if let Err(e) = tx_stderr.send((true, line)) {
-                                println!("Failed to send error message: {}", e);
+                        log::debug!("STDERR: {}", line);
+                        match classify_stderr_line(&line) {
+                            Some(StderrLine::Warning(warning)) => {
+                                let event = DownloadEvent::Warning(warning);
+                                let _ = tx_stderr.send(AppEvent::Job(job_id, event));
                             }
+                            Some(StderrLine::Error(error)) => error_lines.push(error),
+                            Some(StderrLine::Debug(debug)) => last_debug_line = Some(debug),
+                            None => {}
                         }
                     }
                     Err(e) => {
-                        println!("Error reading from stderr: {}", e);
+                        log::warn!("Error reading from stderr: {}", e);
                     }
                 }
             }
+            (error_lines, last_debug_line)
         });
         
         // Wait for the process to complete
-        let status = child.wait();
+        let status = control.wait(&mut child);
         
         // Wait for the output handlers to finish
-        let _ = stdout_handle.join();
-        let _ = stderr_handle.join();
+        let (chapter_files, existing) = stdout_handle.join().unwrap_or_default();
+        let (error_lines, last_debug_line) = stderr_handle.join().unwrap_or_default();
+
+        // One line per finished item, in order
+        let all_outputs: Vec<DownloadedFile> = fs::read_to_string(&paths_file)
+            .map(|paths| {
+                paths
+                    .lines()
+                    .filter_map(|line| serde_json::from_str(line.trim()).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let _ = fs::remove_file(&paths_file);
+
+        // Items whose file was already there. Their output is that file, or
+        // for MP3 the source the extraction was skipped for, which goes
+        let mut outputs = Vec::new();
+        let mut taken = Vec::new();
+        for output in all_outputs {
+            match existing.iter().find(|path| is_output_of(path, &output, format)) {
+                Some(path) => taken.push((output, path.clone())),
+                None => outputs.push(output),
+            }
+        }
+        for (output, path) in &taken {
+            if !same_file(&output.filepath, path) {
+                let _ = fs::remove_file(&output.filepath);
+            }
+        }
+        if let Some((archive, run_archive)) = &run_archive {
+            if run_archive != archive {
+                let left_out: Vec<String> = taken
+                    .iter()
+                    .filter_map(|(output, _)| Some(archive_key(output.extractor_key.as_ref()?, output.id.as_ref()?)))
+                    .collect();
+                if let Err(e) = archive::merge_run_archive(archive, run_archive, &left_out) {
+                    send(DownloadEvent::Warning(format!("Failed to update the download archive: {}", e)));
+                }
+                let _ = fs::remove_file(run_archive);
+            }
+        }
+
+        if matches!(&status, Ok(exit_status) if exit_status.success()) && !chapter_files.is_empty() {
+            send(DownloadEvent::StageChanged(Stage::PostProcessing));
+            if let Err(e) = tag_chapter_files(&outputs, &chapter_files, &control, &send) {
+                send(DownloadEvent::Warning(e));
+            }
+        }
+
+        if let Some(request) = control.stop_request() {
+            if let StopRequest::Cancel { delete_partial: true } = request {
+                cleanup_partial_files(
+                    &destinations.lock().unwrap_or_else(|e| e.into_inner()),
+                    &intermediates.lock().unwrap_or_else(|e| e.into_inner()),
+                );
+            }
+            send(stopped_event(request));
+            return;
+        }
         
         match status {
             Ok(exit_status) => {
                 if exit_status.success() {
-                    let _ = tx.send((false, "Download complete".to_string()));
+                    for (output, path) in taken {
+                        if let Some(id) = output.id {
+                            send(DownloadEvent::NameTaken { id, path });
+                        }
+                    }
+                    send(DownloadEvent::Finished { outputs });
                 } else {
-                    let _ = tx.send((true, format!("Process exited with: {}", exit_status)));
+                    let mut output = error_lines;
+                    output.extend(last_debug_line);
+                    let kind = classify_failure(&output);
+
+                    // yt-dlp's last ERROR line says more than the exit code; without
+                    // one, the tail of a traceback is the next best thing
+                    let message = output
+                        .last()
+                        .cloned()
+                        .unwrap_or_else(|| format!("Process exited with: {}", exit_status));
+                    send(DownloadEvent::Error { kind, message });
                 }
             }
             Err(e) => {
-                let _ = tx.send((true, format!("Failed to wait for process: {}", e)));
+                send(DownloadEvent::Error {
+                    kind: ErrorKind::Launch,
+                    message: format!("Failed to wait for process: {}", e),
+                });
             }
         }
     })
 }
 
-pub fn update_ytdlp(tx: Sender<(bool, String)>) -> thread::JoinHandle<()> {
+fn fetch_thumbnail(url: &str) -> Result<egui::ColorImage, String> {
+    let response = get(url).map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to download thumbnail: {}", response.status()));
+    }
+    let bytes = response.bytes().map_err(|e| e.to_string())?;
+    let image = image::load_from_memory(&bytes)
+        .map_err(|e| e.to_string())?
+        .to_rgba8();
+    let size = [image.width() as usize, image.height() as usize];
+    Ok(egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw()))
+}
+
+// yt-dlp looks for ffmpeg on the PATH too, so this is what it would find
+pub fn ffmpeg_available() -> bool {
+    Command::new("ffmpeg")
+        .arg("-version")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+// Runs `yt-dlp -J` with playlists listed flat, returning the parsed JSON or
+// the error yt-dlp reported
+fn dump_json(url: &str, extra_args: &[&str], cookies_file: Option<&str>) -> Result<serde_json::Value, String> {
+    let ytdlp_path = ensure_ytdlp_exists(&|_, _| {}).map_err(|e| format!("Failed to get yt-dlp: {}", e))?;
+
+    let mut cmd = Command::new(&ytdlp_path);
+    cmd.arg("--dump-single-json")
+       .arg("--flat-playlist")
+       .arg("--no-warnings")
+       .arg("--no-check-certificate")
+       .args(extra_args);
+    if let Some(cookies_file) = cookies_file {
+        cmd.arg("--cookies").arg(cookies_file);
+    }
+    cmd.arg(url);
+
+    let output = cmd.output().map_err(|e| format!("Failed to start yt-dlp: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(stderr
+            .lines()
+            .rev()
+            .find_map(|line| line.trim().strip_prefix("ERROR:"))
+            .map(|line| line.trim().to_string())
+            .unwrap_or_else(|| format!("Process exited with: {}", output.status)));
+    }
+
+    serde_json::from_slice(&output.stdout).map_err(|e| format!("Unreadable metadata: {}", e))
+}
+
+// Reads the URL's metadata with `yt-dlp -J`, then its thumbnail. Playlists
+// are only listed, without visiting every entry
+pub fn fetch_info(url: String, cookies_file: Option<String>, tx: Sender<AppEvent>) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let send = |event: InfoEvent| {
+            let _ = tx.send(AppEvent::Info(event));
+        };
+        let fail = |message: String| {
+            send(InfoEvent::Failed {
+                url: url.clone(),
+                message,
+            });
+        };
+
+        let info = match dump_json(&url, &["--no-playlist"], cookies_file.as_deref()) {
+            Ok(info) => info,
+            Err(message) => return fail(message),
+        };
+        if info.get("_type").and_then(|kind| kind.as_str()) == Some("playlist") {
+            match serde_json::from_value::<PlaylistInfo>(info) {
+                Ok(playlist) => send(InfoEvent::Playlist {
+                    url: url.clone(),
+                    playlist: Box::new(playlist),
+                }),
+                Err(e) => fail(format!("Unreadable playlist: {}", e)),
+            }
+            return;
+        }
+
+        let metadata: VideoMetadata = match serde_json::from_value(info) {
+            Ok(metadata) => metadata,
+            Err(e) => return fail(format!("Unreadable metadata: {}", e)),
+        };
+        let thumbnail_url = metadata.thumbnail_url().map(str::to_string);
+        send(InfoEvent::Fetched {
+            url: url.clone(),
+            metadata: Box::new(metadata),
+        });
+
+        // The card is useful without a thumbnail, so failures are only logged
+        if let Some(thumbnail_url) = thumbnail_url {
+            match fetch_thumbnail(&thumbnail_url) {
+                Ok(image) => send(InfoEvent::Thumbnail {
+                    url: url.clone(),
+                    image,
+                }),
+                Err(e) => log::warn!("Failed to load thumbnail {}: {}", thumbnail_url, e),
+            }
+        }
+    })
+}
+
+// Lists the newest `max_items` entries of a subscribed channel or playlist
+pub fn check_subscription(
+    id: SubscriptionId,
+    url: String,
+    max_items: u32,
+    cookies_file: Option<String>,
+    tx: Sender<AppEvent>,
+) -> thread::JoinHandle<()> {
     thread::spawn(move || {
+        let playlist_end = max_items.max(1).to_string();
+        let result = dump_json(&url, &["--playlist-end", &playlist_end], cookies_file.as_deref())
+            .and_then(|info| {
+                serde_json::from_value::<PlaylistInfo>(info)
+                    .map_err(|e| format!("Unreadable playlist: {}", e))
+            });
+        let event = match result {
+            Ok(playlist) => SubscriptionEvent::Checked {
+                id,
+                playlist: Box::new(playlist),
+            },
+            Err(message) => SubscriptionEvent::Failed { id, message },
+        };
+        let _ = tx.send(AppEvent::Subscription(event));
+    })
+}
+
+pub fn update_ytdlp(tx: Sender<AppEvent>) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let send = |event: UpdaterEvent| {
+            let _ = tx.send(AppEvent::Updater(event));
+        };
         let local_path = get_local_ytdlp_path();
         
         // Remove the existing binary to force a fresh download
         if local_path.exists() {
             if let Err(e) = fs::remove_file(&local_path) {
-                let _ = tx.send((true, format!("Failed to remove existing yt-dlp: {}", e)));
+                send(UpdaterEvent::Failed(format!("Failed to remove existing yt-dlp: {}", e)));
                 return;
             }
         }
         
         // This will download a fresh copy
-        match ensure_ytdlp_exists() {
-            Ok(_) => {
-                let _ = tx.send((false, "yt-dlp updated".to_string()));
-            }
-            Err(e) => {
-                let _ = tx.send((true, format!("Failed to update yt-dlp: {}", e)));
-            }
+        let on_progress = |downloaded_bytes, total_bytes| {
+            send(UpdaterEvent::Progress {
+                downloaded_bytes,
+                total_bytes,
+            });
+        };
+        match ensure_ytdlp_exists(&on_progress) {
+            Ok(_) => send(UpdaterEvent::Finished),
+            Err(e) => send(UpdaterEvent::Failed(format!("Failed to update yt-dlp: {}", e))),
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_merge_lines() {
+        assert_eq!(merges_formats("[info] dQw4w9WgXcQ: Downloading 1 format(s): 137+140"), Some(true));
+        assert_eq!(merges_formats("[info] dQw4w9WgXcQ: Downloading 1 format(s): 22"), Some(false));
+        assert_eq!(merges_formats("[info] Downloading video thumbnail 0 ..."), None);
+        assert_eq!(
+            merger_output(r#"[Merger] Merging formats into "/tmp/A + B.mp4""#),
+            Some("/tmp/A + B.mp4")
+        );
+        assert_eq!(merger_output("[Merger] Something else"), None);
+    }
+
+    #[test]
+    fn reads_existing_file_lines() {
+        assert_eq!(
+            existing_file_line("[download] /d/Song.mp4 has already been downloaded"),
+            Some(("/d/Song.mp4", false))
+        );
+        assert_eq!(
+            existing_file_line("[ExtractAudio] Post-process file /d/Song.mp3 exists, skipping"),
+            Some(("/d/Song.mp3", false))
+        );
+        assert_eq!(existing_file_line("Deleting existing file /d/Song.mp4"), Some(("/d/Song.mp4", true)));
+        assert_eq!(existing_file_line("[ExtractAudio] Destination: /d/Song.mp3"), None);
+    }
+
+    #[test]
+    fn marks_the_name_before_the_extension() {
+        assert_eq!(marked_path(Path::new("/d/Song.mp4"), "temp"), Path::new("/d/Song.temp.mp4"));
+        assert_eq!(marked_path(Path::new("/d/Song"), "temp"), Path::new("/d/Song.temp"));
+    }
+
+    #[test]
+    fn cleanup_keeps_finished_files() {
+        let dir = std::env::temp_dir().join(format!("download-cleanup-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let finished = dir.join("f-16.mp4");
+        let stream = dir.join("Clip.f137.mp4");
+        let merged = dir.join("Clip.mp4");
+        for file in [&finished, &stream, &dir.join("Clip.f140.m4a.part"), &dir.join("Clip.temp.mp4")] {
+            fs::write(file, b"").unwrap();
+        }
+
+        cleanup_partial_files(
+            &[finished, stream.clone(), dir.join("Clip.f140.m4a"), merged],
+            &[stream],
+        );
+        let left: Vec<_> = fs::read_dir(&dir).unwrap().flatten().map(|e| e.file_name()).collect();
+        assert_eq!(left, ["f-16.mp4"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,200 @@
+// Why a job failed, so the UI can explain it and suggest a fix without
+// matching on message text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    // The managed yt-dlp binary could not be fetched or prepared
+    YtdlpUnavailable,
+    // yt-dlp could not be started or waited on
+    Launch,
+    PrivateVideo,
+    AgeRestricted,
+    GeoBlocked,
+    // HTTP 403
+    Forbidden,
+    // HTTP 429
+    RateLimited,
+    // HTTP 5xx
+    ServerError,
+    FormatUnavailable,
+    FfmpegMissing,
+    DiskFull,
+    NetworkDown,
+    // The site changed and the extractor can no longer read it
+    ExtractorOutdated,
+    // yt-dlp failed for a reason not covered above
+    Unknown,
+}
+
+// What the user can do about a failure, offered as a button next to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Remedy {
+    UpdateYtdlp,
+    ProvideCookies,
+}
+
+// Lowercase fragments of yt-dlp error output for each kind, checked in
+// order so the more specific ones win (a 429 is also "unable to download")
+const PATTERNS: [(ErrorKind, &[&str]); 11] = [
+    (
+        ErrorKind::FfmpegMissing,
+        &["ffmpeg not found", "ffmpeg is not installed", "ffprobe and ffmpeg not found"],
+    ),
+    (
+        ErrorKind::DiskFull,
+        &["no space left on device", "errno 28", "disk quota exceeded"],
+    ),
+    (
+        ErrorKind::PrivateVideo,
+        &["private video", "video is private", "members-only", "join this channel"],
+    ),
+    (
+        ErrorKind::AgeRestricted,
+        &["confirm your age", "age-restricted", "age restricted", "inappropriate for some users"],
+    ),
+    (
+        ErrorKind::GeoBlocked,
+        &["not available in your country", "geo restrict", "geo-restrict", "blocked it in your country"],
+    ),
+    (ErrorKind::RateLimited, &["http error 429", "too many requests"]),
+    (ErrorKind::Forbidden, &["http error 403"]),
+    (
+        ErrorKind::ServerError,
+        &["http error 500", "http error 502", "http error 503", "http error 504"],
+    ),
+    (
+        ErrorKind::FormatUnavailable,
+        &["requested format is not available", "requested format not available"],
+    ),
+    (
+        ErrorKind::ExtractorOutdated,
+        &["unable to extract", "signature extraction failed", "nsig extraction failed"],
+    ),
+    (
+        ErrorKind::NetworkDown,
+        &[
+            "unable to download webpage",
+            "temporary failure in name resolution",
+            "name or service not known",
+            "network is unreachable",
+            "connection refused",
+            "connection reset",
+            "timed out",
+        ],
+    ),
+];
+
+// Classifies what yt-dlp wrote to stderr before failing
+pub fn classify_failure(lines: &[String]) -> ErrorKind {
+    let text = lines.join("\n").to_lowercase();
+    PATTERNS
+        .iter()
+        .find(|(_, fragments)| fragments.iter().any(|fragment| text.contains(fragment)))
+        .map(|(kind, _)| *kind)
+        .unwrap_or(ErrorKind::Unknown)
+}
+
+impl ErrorKind {
+    // Localization key and English fallback explaining the failure
+    pub fn description(&self) -> (&'static str, &'static str) {
+        match self {
+            Self::YtdlpUnavailable => ("error-ytdlp-unavailable", "yt-dlp could not be downloaded."),
+            Self::Launch => ("error-launch", "yt-dlp could not be started."),
+            Self::PrivateVideo => ("error-private", "This video is private or members-only."),
+            Self::AgeRestricted => ("error-age-restricted", "This video is age-restricted."),
+            Self::GeoBlocked => ("error-geo-blocked", "This video is not available in your country."),
+            Self::Forbidden => ("error-forbidden", "The server refused the download (HTTP 403)."),
+            Self::RateLimited => ("error-rate-limited", "Too many requests; the site is throttling downloads (HTTP 429)."),
+            Self::ServerError => ("error-server", "The site had a temporary server error."),
+            Self::FormatUnavailable => ("error-format-unavailable", "The requested format is not available for this video."),
+            Self::FfmpegMissing => ("error-ffmpeg-missing", "ffmpeg is required for this download but was not found."),
+            Self::DiskFull => ("error-disk-full", "The download directory is out of space."),
+            Self::NetworkDown => ("error-network", "The site could not be reached."),
+            Self::ExtractorOutdated => ("error-extractor-outdated", "yt-dlp could not read this site; it may be out of date."),
+            Self::Unknown => ("error-unknown", "The download failed."),
+        }
+    }
+
+    // Localization key and English fallback suggesting what to do next
+    pub fn hint(&self) -> (&'static str, &'static str) {
+        match self {
+            Self::YtdlpUnavailable => ("hint-ytdlp-unavailable", "Check your internet connection and try again."),
+            Self::Launch => ("hint-launch", "Try updating yt-dlp."),
+            Self::PrivateVideo => ("hint-private", "Provide cookies from an account that has access to it."),
+            Self::AgeRestricted => ("hint-age-restricted", "Provide cookies from a signed-in account to confirm your age."),
+            Self::GeoBlocked => ("hint-geo-blocked", "It can only be downloaded from a region where it is published."),
+            Self::Forbidden => ("hint-forbidden", "Update yt-dlp or provide cookies, then retry."),
+            Self::RateLimited => ("hint-rate-limited", "Wait a while before retrying, or lower the number of parallel downloads."),
+            Self::ServerError => ("hint-server", "Retry in a few minutes."),
+            Self::FormatUnavailable => ("hint-format-unavailable", "Choose another format and retry."),
+            Self::FfmpegMissing => ("hint-ffmpeg-missing", "Install ffmpeg (e.g. sudo apt install ffmpeg) and retry."),
+            Self::DiskFull => ("hint-disk-full", "Free up space or choose another directory, then retry."),
+            Self::NetworkDown => ("hint-network", "Check your internet connection and retry."),
+            Self::ExtractorOutdated => ("hint-extractor-outdated", "Update yt-dlp and retry."),
+            Self::Unknown => ("hint-unknown", "See the details below, or retry."),
+        }
+    }
+
+    // Failures likely to go away on their own, worth retrying after a delay
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::NetworkDown | Self::ServerError | Self::RateLimited)
+    }
+
+    pub fn remedy(&self) -> Option<Remedy> {
+        match self {
+            Self::ExtractorOutdated | Self::Forbidden | Self::Launch => Some(Remedy::UpdateYtdlp),
+            Self::PrivateVideo | Self::AgeRestricted => Some(Remedy::ProvideCookies),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(lines: &[&str]) -> ErrorKind {
+        classify_failure(&lines.iter().map(|line| line.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn recognises_common_failures() {
+        assert_eq!(
+            classify(&["ERROR: [youtube] abc: Private video. Sign in if you've been granted access"]),
+            ErrorKind::PrivateVideo
+        );
+        assert_eq!(
+            classify(&["ERROR: unable to download video data: HTTP Error 403: Forbidden"]),
+            ErrorKind::Forbidden
+        );
+        assert_eq!(
+            classify(&["ERROR: Postprocessing: ffprobe and ffmpeg not found. Please install or provide the path"]),
+            ErrorKind::FfmpegMissing
+        );
+        assert_eq!(
+            classify(&["OSError: [Errno 28] No space left on device"]),
+            ErrorKind::DiskFull
+        );
+    }
+
+    #[test]
+    fn prefers_the_more_specific_kind() {
+        // A 429 also reads as "unable to download webpage"
+        assert_eq!(
+            classify(&["ERROR: [youtube] abc: Unable to download webpage: HTTP Error 429: Too Many Requests"]),
+            ErrorKind::RateLimited
+        );
+    }
+
+    #[test]
+    fn falls_back_to_unknown() {
+        assert_eq!(classify(&[]), ErrorKind::Unknown);
+        assert_eq!(classify(&["ERROR: something new went wrong"]), ErrorKind::Unknown);
+        // yt-dlp appends the bug report hint to many unrelated errors
+        assert_eq!(
+            classify(&[
+                "ERROR: [generic] abc: Access forbidden by the playlist owner; please report this issue on https://github.com/yt-dlp/yt-dlp/issues"
+            ]),
+            ErrorKind::Unknown
+        );
+    }
+}
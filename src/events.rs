@@ -0,0 +1,119 @@
+use std::path::PathBuf;
+
+use crate::errors::ErrorKind;
+use crate::models::{DownloadedFile, JobId, PlaylistInfo, VideoMetadata};
+use crate::subscriptions::SubscriptionId;
+
+// Step of a job yt-dlp is currently working on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    // A single file holding both video and audio
+    Downloading,
+    // The video-only stream of a format that is merged afterwards
+    Video,
+    // The audio-only stream, or the source of an MP3 extraction
+    Audio,
+    Merging,
+    ExtractingAudio,
+    // Writing thumbnails, metadata or subtitles into the file
+    Embedding,
+    Moving,
+    // Any other post-processor, e.g. container fixups
+    PostProcessing,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DownloadEvent {
+    Started,
+    Progress {
+        // None when neither the size nor the fragment count is known yet
+        percent: Option<f32>,
+        downloaded_bytes: Option<u64>,
+        total_bytes: Option<u64>,
+        // Whether total_bytes is yt-dlp's estimate rather than the real size
+        total_is_estimate: bool,
+        // Bytes per second
+        speed: Option<f64>,
+        // Seconds remaining
+        eta: Option<u64>,
+        // Current fragment and fragment count for fragmented (HLS/DASH) streams
+        fragments: Option<(u64, u64)>,
+    },
+    StageChanged(Stage),
+    // A run covering several items moved on to item `index` of `count`
+    ItemChanged { index: u64, count: u64 },
+    // A file yt-dlp is about to write
+    Destination(PathBuf),
+    // A stream yt-dlp is about to write and later merge into the output
+    Intermediate(PathBuf),
+    // The output file existed already and was left alone or, when
+    // `replaced`, deleted to download it again
+    ExistingFile { path: PathBuf, replaced: bool },
+    // Item `id` would have been written to `path`, which existed already,
+    // so the run left it alone and kept it out of the archive
+    NameTaken { id: String, path: PathBuf },
+    Warning(String),
+    Error {
+        kind: ErrorKind,
+        message: String,
+    },
+    // Every file the run left on disk, in the order yt-dlp finished them
+    Finished {
+        outputs: Vec<DownloadedFile>,
+    },
+    Paused,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UpdaterEvent {
+    Progress {
+        downloaded_bytes: u64,
+        total_bytes: Option<u64>,
+    },
+    Finished,
+    Failed(String),
+}
+
+// Results of fetching metadata for a URL, tagged with that URL so answers
+// for a URL the user has since replaced can be ignored
+pub enum InfoEvent {
+    Fetched {
+        url: String,
+        metadata: Box<VideoMetadata>,
+    },
+    Playlist {
+        url: String,
+        playlist: Box<PlaylistInfo>,
+    },
+    Thumbnail {
+        url: String,
+        image: egui::ColorImage,
+    },
+    Failed {
+        url: String,
+        message: String,
+    },
+}
+
+// Results of checking a subscription for new entries
+pub enum SubscriptionEvent {
+    Checked {
+        id: SubscriptionId,
+        playlist: Box<PlaylistInfo>,
+    },
+    Failed {
+        id: SubscriptionId,
+        message: String,
+    },
+}
+
+// Everything background threads report back to the UI thread
+pub enum AppEvent {
+    Job(JobId, DownloadEvent),
+    Updater(UpdaterEvent),
+    Info(InfoEvent),
+    Subscription(SubscriptionEvent),
+    // A file or folder could not be shown to the user
+    OpenFailed(String),
+}
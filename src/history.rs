@@ -0,0 +1,351 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::models::{DownloadFormat, DownloadJob, JobStatus};
+use crate::storage::unix_now;
+
+pub type HistoryId = u64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Outcome {
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+// A finished download as kept in the History tab
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: HistoryId,
+    pub url: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    // yt-dlp extractor, e.g. "Youtube"
+    #[serde(default)]
+    pub extractor: Option<String>,
+    // The site's own video ID
+    #[serde(default)]
+    pub media_id: Option<String>,
+    pub format: DownloadFormat,
+    #[serde(default)]
+    pub format_spec: Option<String>,
+    pub download_dir: String,
+    #[serde(default)]
+    pub output_path: Option<PathBuf>,
+    // Every file of the download; `output_path` is the last of them
+    #[serde(default)]
+    pub output_paths: Vec<PathBuf>,
+    // Bytes on disk when the download finished
+    #[serde(default)]
+    pub size: Option<u64>,
+    // Seconds of media
+    #[serde(default)]
+    pub duration: Option<f64>,
+    // Unix times
+    pub queued_at: u64,
+    #[serde(default)]
+    pub started_at: Option<u64>,
+    pub finished_at: u64,
+    pub outcome: Outcome,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl HistoryEntry {
+    // Every text field, lowercased, for full-text search
+    fn search_text(&self) -> String {
+        // Entries saved before `output_paths` existed only have the last path
+        let paths = if self.output_paths.is_empty() {
+            self.output_path.iter().collect::<Vec<_>>()
+        } else {
+            self.output_paths.iter().collect()
+        };
+        let paths = paths
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        [
+            Some(self.url.as_str()),
+            self.title.as_deref(),
+            self.extractor.as_deref(),
+            self.media_id.as_deref(),
+            Some(self.download_dir.as_str()),
+            Some(paths.as_str()),
+            self.error.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("\n")
+        .to_lowercase()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Period {
+    #[default]
+    Any,
+    Day,
+    Week,
+    Month,
+}
+
+impl Period {
+    fn seconds(self) -> Option<u64> {
+        match self {
+            Period::Any => None,
+            Period::Day => Some(24 * 3600),
+            Period::Week => Some(7 * 24 * 3600),
+            Period::Month => Some(30 * 24 * 3600),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HistoryFilter {
+    pub search: String,
+    pub outcome: Option<Outcome>,
+    pub format: Option<DownloadFormat>,
+    pub period: Period,
+}
+
+impl HistoryFilter {
+    // `needle` is the trimmed, lowercased search and `search_text` the
+    // entry's cached HistoryEntry::search_text
+    fn matches(&self, entry: &HistoryEntry, search_text: &str, needle: &str, now: u64) -> bool {
+        if self.outcome.is_some_and(|outcome| outcome != entry.outcome) {
+            return false;
+        }
+        if self.format.is_some_and(|format| format != entry.format) {
+            return false;
+        }
+        if let Some(seconds) = self.period.seconds() {
+            if now.saturating_sub(entry.finished_at) > seconds {
+                return false;
+            }
+        }
+        needle.is_empty() || search_text.contains(needle)
+    }
+}
+
+// Every finished download, oldest first, saved to the config dir whenever
+// it changes
+#[derive(Default)]
+pub struct History {
+    entries: Vec<HistoryEntry>,
+    // Search text of each entry, at the same index, so filtering does not
+    // rebuild it for every entry on every frame
+    search_texts: Vec<String>,
+    next_id: HistoryId,
+    // Changed since last saved
+    dirty: bool,
+}
+
+impl History {
+    pub fn from_saved(entries: Vec<HistoryEntry>) -> Self {
+        let next_id = entries.iter().map(|entry| entry.id).max().unwrap_or(0);
+        let search_texts = entries.iter().map(HistoryEntry::search_text).collect();
+        Self {
+            entries,
+            search_texts,
+            next_id,
+            dirty: false,
+        }
+    }
+
+    fn reindex(&mut self) {
+        self.search_texts = self.entries.iter().map(HistoryEntry::search_text).collect();
+    }
+
+    // Records a job that just reached a final state
+    pub fn record(&mut self, job: &DownloadJob) {
+        let (outcome, error) = match &job.status {
+            JobStatus::Completed => (Outcome::Completed, None),
+            JobStatus::Cancelled => (Outcome::Cancelled, None),
+            JobStatus::Failed(message) => (Outcome::Failed, Some(message.clone())),
+            _ => return,
+        };
+        let sizes = job
+            .output_paths
+            .iter()
+            .filter_map(|path| std::fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .collect::<Vec<_>>();
+        let size = (!sizes.is_empty()).then(|| sizes.iter().sum());
+
+        self.next_id += 1;
+        self.entries.push(HistoryEntry {
+            id: self.next_id,
+            url: job.url.clone(),
+            title: job.title.clone(),
+            extractor: job.extractor.clone(),
+            media_id: job.media_id.clone(),
+            format: job.format,
+            format_spec: job.format_spec.clone(),
+            download_dir: job.download_dir.clone(),
+            output_path: job.output_path.clone(),
+            output_paths: job.output_paths.clone(),
+            size,
+            duration: job.duration,
+            queued_at: job.queued_at,
+            started_at: job.started_at,
+            finished_at: unix_now(),
+            outcome,
+            error,
+        });
+        self.search_texts.extend(self.entries.last().map(HistoryEntry::search_text));
+        self.dirty = true;
+    }
+
+    // Adds entries from another machine, skipping ones already present.
+    // Returns how many were added
+    pub fn import(&mut self, entries: Vec<HistoryEntry>) -> usize {
+        let mut added = 0;
+        for mut entry in entries {
+            let duplicate = self.entries.iter().any(|existing| {
+                existing.url == entry.url && existing.finished_at == entry.finished_at
+            });
+            if duplicate {
+                continue;
+            }
+            self.next_id += 1;
+            entry.id = self.next_id;
+            self.entries.push(entry);
+            added += 1;
+        }
+        if added > 0 {
+            // Keep the list in the order downloads finished
+            self.entries.sort_by_key(|entry| entry.finished_at);
+            self.reindex();
+            self.dirty = true;
+        }
+        added
+    }
+
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    // Entries the filter lets through, newest first
+    pub fn matching(&self, filter: &HistoryFilter, now: u64) -> Vec<&HistoryEntry> {
+        let needle = filter.search.trim().to_lowercase();
+        self.entries
+            .iter()
+            .zip(&self.search_texts)
+            .rev()
+            .filter(|(entry, search_text)| filter.matches(entry, search_text, &needle, now))
+            .map(|(entry, _)| entry)
+            .collect()
+    }
+
+    pub fn get(&self, id: HistoryId) -> Option<&HistoryEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    pub fn remove(&mut self, id: HistoryId) {
+        self.entries.retain(|entry| entry.id != id);
+        self.reindex();
+        self.dirty = true;
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn mark_saved(&mut self) {
+        self.dirty = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 24 * 3600;
+
+    fn entry(url: &str, title: &str, outcome: Outcome, finished_at: u64) -> HistoryEntry {
+        HistoryEntry {
+            id: 0,
+            url: url.to_string(),
+            title: Some(title.to_string()),
+            extractor: Some("Youtube".to_string()),
+            media_id: None,
+            format: DownloadFormat::MP4,
+            format_spec: None,
+            download_dir: "/home/user/Videos".to_string(),
+            output_path: None,
+            output_paths: Vec::new(),
+            size: None,
+            duration: None,
+            queued_at: finished_at,
+            started_at: None,
+            finished_at,
+            outcome,
+            error: None,
+        }
+    }
+
+    fn titles(entries: Vec<&HistoryEntry>) -> Vec<&str> {
+        entries.iter().filter_map(|entry| entry.title.as_deref()).collect()
+    }
+
+    fn history() -> History {
+        let mut entries = vec![
+            entry("https://a", "Old Talk", Outcome::Completed, 10 * DAY),
+            entry("https://b", "Recent Song", Outcome::Failed, 19 * DAY),
+            entry("https://c", "Newest Talk", Outcome::Completed, 20 * DAY),
+        ];
+        for (id, entry) in entries.iter_mut().enumerate() {
+            entry.id = id as HistoryId + 1;
+        }
+        History::from_saved(entries)
+    }
+
+    #[test]
+    fn searches_case_insensitively_newest_first() {
+        let filter = HistoryFilter {
+            search: "  TALK ".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(titles(history().matching(&filter, 20 * DAY)), ["Newest Talk", "Old Talk"]);
+    }
+
+    #[test]
+    fn combines_outcome_format_and_period() {
+        let history = history();
+        let now = 20 * DAY;
+        let completed = HistoryFilter {
+            outcome: Some(Outcome::Completed),
+            ..Default::default()
+        };
+        assert_eq!(titles(history.matching(&completed, now)), ["Newest Talk", "Old Talk"]);
+
+        let this_week = HistoryFilter {
+            period: Period::Week,
+            ..completed
+        };
+        assert_eq!(titles(history.matching(&this_week, now)), ["Newest Talk"]);
+
+        let mp3 = HistoryFilter {
+            format: Some(DownloadFormat::MP3),
+            ..Default::default()
+        };
+        assert!(history.matching(&mp3, now).is_empty());
+    }
+
+    #[test]
+    fn import_skips_entries_already_present() {
+        let mut history = history();
+        let added = history.import(vec![
+            entry("https://b", "Recent Song", Outcome::Failed, 19 * DAY),
+            entry("https://d", "Imported", Outcome::Completed, 15 * DAY),
+        ]);
+
+        assert_eq!(added, 1);
+        assert!(history.is_dirty());
+        let urls: Vec<&str> = history.entries().iter().map(|entry| entry.url.as_str()).collect();
+        assert_eq!(urls, ["https://a", "https://d", "https://b", "https://c"]);
+        assert_eq!(history.entries()[1].id, 4);
+    }
+}
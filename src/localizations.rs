@@ -56,6 +56,187 @@ impl Localizations {
         en.insert("enter-url", "Please enter a URL");
         en.insert("no-url", "No URL provided");
         en.insert("select-directory", "Select download directory");
+        en.insert("download-complete", "Download complete");
+        en.insert("update-complete", "Update complete");
+        en.insert("job-added", "Added to queue");
+        en.insert("queue-title", "Downloads");
+        en.insert("queue-empty", "No downloads yet");
+        en.insert("clear-finished", "Clear finished");
+        en.insert("remove-button", "Remove");
+        en.insert("status-queued", "Queued");
+        en.insert("parallel-downloads", "Parallel downloads:");
+        en.insert("delete-partial", "Delete partial files when cancelling");
+        en.insert("cancel-button", "Cancel");
+        en.insert("status-cancelled", "Cancelled");
+        en.insert("pause-button", "Pause");
+        en.insert("resume-button", "Resume");
+        en.insert("status-paused", "Paused");
+        en.insert("recovery-found", "Interrupted downloads found:");
+        en.insert("recovery-resume", "Resume all");
+        en.insert("recovery-discard", "Discard");
+        en.insert("eta-label", "ETA");
+        en.insert("stage-downloading", "Downloading");
+        en.insert("stage-video", "Downloading video");
+        en.insert("stage-audio", "Downloading audio");
+        en.insert("stage-merging", "Merging video and audio");
+        en.insert("stage-extracting", "Extracting audio");
+        en.insert("stage-embedding", "Embedding metadata");
+        en.insert("stage-moving", "Moving files");
+        en.insert("stage-processing", "Processing");
+        en.insert("fragment-label", "Fragment");
+        en.insert("warnings-label", "Warnings");
+        en.insert("error-ytdlp-unavailable", "yt-dlp could not be downloaded.");
+        en.insert("error-launch", "yt-dlp could not be started.");
+        en.insert("error-private", "This video is private or members-only.");
+        en.insert("error-age-restricted", "This video is age-restricted.");
+        en.insert("error-geo-blocked", "This video is not available in your country.");
+        en.insert("error-forbidden", "The server refused the download (HTTP 403).");
+        en.insert("error-rate-limited", "Too many requests; the site is throttling downloads (HTTP 429).");
+        en.insert("error-format-unavailable", "The requested format is not available for this video.");
+        en.insert("error-ffmpeg-missing", "ffmpeg is required for this download but was not found.");
+        en.insert("error-disk-full", "The download directory is out of space.");
+        en.insert("error-network", "The site could not be reached.");
+        en.insert("error-extractor-outdated", "yt-dlp could not read this site; it may be out of date.");
+        en.insert("error-unknown", "The download failed.");
+        en.insert("hint-ytdlp-unavailable", "Check your internet connection and try again.");
+        en.insert("hint-launch", "Try updating yt-dlp.");
+        en.insert("hint-private", "Provide cookies from an account that has access to it.");
+        en.insert("hint-age-restricted", "Provide cookies from a signed-in account to confirm your age.");
+        en.insert("hint-geo-blocked", "It can only be downloaded from a region where it is published.");
+        en.insert("hint-forbidden", "Update yt-dlp or provide cookies, then retry.");
+        en.insert("hint-rate-limited", "Wait a while before retrying, or lower the number of parallel downloads.");
+        en.insert("hint-format-unavailable", "Choose another format and retry.");
+        en.insert("hint-ffmpeg-missing", "Install ffmpeg (e.g. sudo apt install ffmpeg) and retry.");
+        en.insert("hint-disk-full", "Free up space or choose another directory, then retry.");
+        en.insert("hint-network", "Check your internet connection and retry.");
+        en.insert("hint-extractor-outdated", "Update yt-dlp and retry.");
+        en.insert("hint-unknown", "See the details below, or retry.");
+        en.insert("provide-cookies", "Provide cookies");
+        en.insert("retry-button", "Retry");
+        en.insert("error-details", "Details");
+        en.insert("cookies-label", "Cookies file:");
+        en.insert("clear-button", "Clear");
+        en.insert("choose-button", "Choose...");
+        en.insert("update-pending", "yt-dlp will be updated once running downloads finish");
+        en.insert("error-server", "The site had a temporary server error.");
+        en.insert("hint-server", "Retry in a few minutes.");
+        en.insert("status-retrying", "Retrying in");
+        en.insert("attempt-label", "attempt");
+        en.insert("retry-attempts", "Max attempts:");
+        en.insert("retry-delay", "First retry after (s):");
+        en.insert("saved-to", "Saved to:");
+        en.insert("open-file", "Open");
+        en.insert("show-in-folder", "Show in folder");
+        en.insert("fetch-info", "Fetch info");
+        en.insert("views-label", "views");
+        en.insert("estimated-size", "Estimated size:");
+        en.insert("choose-format", "Choose format…");
+        en.insert("format-chooser-title", "Choose format");
+        en.insert("format-chooser-hint", "Pick a video stream and an audio stream, or a single stream that has both");
+        en.insert("custom-format-label", "Format:");
+        en.insert("reset-format", "Reset");
+        en.insert("use-format", "Use this format");
+        en.insert("audio-only", "audio only");
+        en.insert("format-column-id", "ID");
+        en.insert("format-column-ext", "Container");
+        en.insert("format-column-resolution", "Resolution");
+        en.insert("format-column-fps", "FPS");
+        en.insert("format-column-vcodec", "Video codec");
+        en.insert("format-column-acodec", "Audio codec");
+        en.insert("format-column-bitrate", "Bitrate");
+        en.insert("format-column-size", "Size");
+        en.insert("playlist-detected", "Playlist detected, choose the entries to download");
+        en.insert("no-entries-selected", "No playlist entries selected");
+        en.insert("playlist-entries", "entries");
+        en.insert("select-all", "Select all");
+        en.insert("select-none", "Select none");
+        en.insert("items-label", "Items");
+        en.insert("select-range", "Select range");
+        en.insert("enqueue-selected", "Add selected to queue");
+        en.insert("item-label", "Item");
+        en.insert("total-label", "total");
+        en.insert("tab-downloads", "Downloads");
+        en.insert("tab-subscriptions", "Subscriptions");
+        en.insert("add-subscription", "Add subscription");
+        en.insert("subscription-url", "Channel or playlist URL");
+        en.insert("subscription-url-hint", "e.g. a channel's Videos page");
+        en.insert("subscription-name", "Name");
+        en.insert("filter-title", "Title contains");
+        en.insert("filter-duration", "Duration (minutes, 0 = any)");
+        en.insert("filter-max-items", "Newest entries to check");
+        en.insert("skip-existing", "Only download uploads from now on");
+        en.insert("add-button", "Add");
+        en.insert("since-last-check", "Since last check:");
+        en.insert("never-checked", "Not checked yet");
+        en.insert("new-videos", "new videos");
+        en.insert("checking", "Checking...");
+        en.insert("check-now", "Check now");
+        en.insert("check-interval", "Check every (minutes):");
+        en.insert("tab-archive", "Archive");
+        en.insert("archive-description", "Videos listed here are skipped by every download. Remove one to download it again.");
+        en.insert("archive-search", "Search by site or video ID");
+        en.insert("import-button", "Import...");
+        en.insert("export-button", "Export...");
+        en.insert("reload-button", "Reload");
+        en.insert("archive-entries", "entries");
+        en.insert("archive-imported", "Entries imported:");
+        en.insert("archive-exported", "Archive exported to");
+        en.insert("tab-history", "History");
+        en.insert("outcome-completed", "Completed");
+        en.insert("outcome-failed", "Failed");
+        en.insert("outcome-cancelled", "Cancelled");
+        en.insert("period-any", "Any time");
+        en.insert("period-day", "Last 24 hours");
+        en.insert("period-week", "Last 7 days");
+        en.insert("period-month", "Last 30 days");
+        en.insert("filter-all", "All");
+        en.insert("history-search", "Search URL, title, site, path or error");
+        en.insert("download-again", "Download again");
+        en.insert("edit-and-download", "Change settings…");
+        en.insert("delete-button", "Delete");
+        en.insert("export-json", "Export JSON...");
+        en.insert("export-csv", "Export CSV...");
+        en.insert("import-json", "Import JSON...");
+        en.insert("records-exported", "Records exported to");
+        en.insert("records-imported", "Records imported:");
+        en.insert("template-title", "Filename template");
+        en.insert("template-insert", "Insert:");
+        en.insert("template-reset", "Reset");
+        en.insert("template-preview", "Saved as:");
+        en.insert("template-preview-hint", "Fetch info for a URL to preview the file name");
+        en.insert("collision-label", "If the file exists:");
+        en.insert("collision-skip", "Skip");
+        en.insert("collision-overwrite", "Overwrite");
+        en.insert("collision-rename", "Save under a new name");
+        en.insert("status-already-present", "Already present, skipped");
+        en.insert("collision-kept", "Existing file kept:");
+        en.insert("collision-replaced", "Existing file replaced:");
+        en.insert("collision-renamed", "Name taken, saved as:");
+        en.insert("subtitles-title", "Subtitles");
+        en.insert("subtitles-automatic", "Include automatic captions");
+        en.insert("subtitles-none", "No subtitles available for this URL");
+        en.insert("subtitles-automatic-list", "Automatic captions:");
+        en.insert("subtitles-hint", "Fetch info for a URL to list its subtitle languages");
+        en.insert("subtitles-selected", "Languages:");
+        en.insert("subtitles-sidecar", "Separate files");
+        en.insert("subtitles-embed", "Embed into the MP4");
+        en.insert("subtitles-format", "File format:");
+        en.insert("subtitles-mp3", "MP3 files can't hold subtitles, so they are saved as separate files");
+        en.insert("embed-title", "Cover art, tags and chapters");
+        en.insert("embed-thumbnail", "Embed the thumbnail as cover art");
+        en.insert("embed-metadata", "Write title, artist, album, date and description");
+        en.insert("embed-chapters", "Embed chapters");
+        en.insert("embed-ffmpeg-missing", "ffmpeg was not found, so MP3 downloads will fail and others will be saved without these or embedded and converted subtitles. Install ffmpeg (e.g. sudo apt install ffmpeg) to use them.");
+        en.insert("embed-recheck", "Check again");
+        en.insert("embed-skipped", "ffmpeg was not found, so nothing was embedded, converted or split");
+        en.insert("split-chapters", "Also save each chapter as its own file");
+        en.insert("chapters-title", "Chapters");
+        en.insert("file-missing", "The file was moved or deleted");
+        en.insert("archive-locked", "Entries can be removed once running downloads finish.");
+        en.insert("template-preview-unavailable", "Preview unavailable");
+        en.insert("collision-more", "more");
+        en.insert("collision-renamed-id", "Name taken, saved with its id added:");
+        en.insert("waiting-for-info", "Fetching info, the download starts once it arrives");
         translations.insert("en-US", en);
 
         // Spanish translations
@@ -82,6 +263,187 @@ impl Localizations {
         es.insert("enter-url", "Por favor ingrese una URL");
         es.insert("no-url", "No se proporcionó una URL");
         es.insert("select-directory", "Selecciona el directorio");
+        es.insert("download-complete", "Descarga completada");
+        es.insert("update-complete", "Actualización completada");
+        es.insert("job-added", "Agregado a la cola");
+        es.insert("queue-title", "Descargas");
+        es.insert("queue-empty", "Aún no hay descargas");
+        es.insert("clear-finished", "Limpiar terminadas");
+        es.insert("remove-button", "Quitar");
+        es.insert("status-queued", "En cola");
+        es.insert("parallel-downloads", "Descargas simultáneas:");
+        es.insert("delete-partial", "Borrar archivos parciales al cancelar");
+        es.insert("cancel-button", "Cancelar");
+        es.insert("status-cancelled", "Cancelada");
+        es.insert("pause-button", "Pausar");
+        es.insert("resume-button", "Reanudar");
+        es.insert("status-paused", "En pausa");
+        es.insert("recovery-found", "Descargas interrumpidas encontradas:");
+        es.insert("recovery-resume", "Reanudar todas");
+        es.insert("recovery-discard", "Descartar");
+        es.insert("eta-label", "Restante");
+        es.insert("stage-downloading", "Descargando");
+        es.insert("stage-video", "Descargando video");
+        es.insert("stage-audio", "Descargando audio");
+        es.insert("stage-merging", "Uniendo video y audio");
+        es.insert("stage-extracting", "Extrayendo audio");
+        es.insert("stage-embedding", "Incrustando metadatos");
+        es.insert("stage-moving", "Moviendo archivos");
+        es.insert("stage-processing", "Procesando");
+        es.insert("fragment-label", "Fragmento");
+        es.insert("warnings-label", "Advertencias");
+        es.insert("error-ytdlp-unavailable", "No se pudo descargar yt-dlp.");
+        es.insert("error-launch", "No se pudo iniciar yt-dlp.");
+        es.insert("error-private", "Este video es privado o solo para miembros.");
+        es.insert("error-age-restricted", "Este video tiene restricción de edad.");
+        es.insert("error-geo-blocked", "Este video no está disponible en tu país.");
+        es.insert("error-forbidden", "El servidor rechazó la descarga (HTTP 403).");
+        es.insert("error-rate-limited", "Demasiadas solicitudes; el sitio está limitando las descargas (HTTP 429).");
+        es.insert("error-format-unavailable", "El formato solicitado no está disponible para este video.");
+        es.insert("error-ffmpeg-missing", "Esta descarga necesita ffmpeg, pero no se encontró.");
+        es.insert("error-disk-full", "No queda espacio en el directorio de descarga.");
+        es.insert("error-network", "No se pudo conectar con el sitio.");
+        es.insert("error-extractor-outdated", "yt-dlp no pudo leer este sitio; puede estar desactualizado.");
+        es.insert("error-unknown", "La descarga falló.");
+        es.insert("hint-ytdlp-unavailable", "Revisa tu conexión a internet e inténtalo de nuevo.");
+        es.insert("hint-launch", "Intenta actualizar yt-dlp.");
+        es.insert("hint-private", "Proporciona cookies de una cuenta con acceso al video.");
+        es.insert("hint-age-restricted", "Proporciona cookies de una cuenta con sesión iniciada para confirmar tu edad.");
+        es.insert("hint-geo-blocked", "Solo puede descargarse desde una región donde esté publicado.");
+        es.insert("hint-forbidden", "Actualiza yt-dlp o proporciona cookies y vuelve a intentarlo.");
+        es.insert("hint-rate-limited", "Espera un rato antes de reintentar o reduce las descargas simultáneas.");
+        es.insert("hint-format-unavailable", "Elige otro formato y vuelve a intentarlo.");
+        es.insert("hint-ffmpeg-missing", "Instala ffmpeg (p. ej. sudo apt install ffmpeg) y vuelve a intentarlo.");
+        es.insert("hint-disk-full", "Libera espacio o elige otro directorio y vuelve a intentarlo.");
+        es.insert("hint-network", "Revisa tu conexión a internet y vuelve a intentarlo.");
+        es.insert("hint-extractor-outdated", "Actualiza yt-dlp y vuelve a intentarlo.");
+        es.insert("hint-unknown", "Consulta los detalles o vuelve a intentarlo.");
+        es.insert("provide-cookies", "Proporcionar cookies");
+        es.insert("retry-button", "Reintentar");
+        es.insert("error-details", "Detalles");
+        es.insert("cookies-label", "Archivo de cookies:");
+        es.insert("clear-button", "Quitar");
+        es.insert("choose-button", "Elegir...");
+        es.insert("update-pending", "yt-dlp se actualizará cuando terminen las descargas en curso");
+        es.insert("error-server", "El sitio tuvo un error temporal del servidor.");
+        es.insert("hint-server", "Vuelve a intentarlo en unos minutos.");
+        es.insert("status-retrying", "Reintentando en");
+        es.insert("attempt-label", "intento");
+        es.insert("retry-attempts", "Intentos máximos:");
+        es.insert("retry-delay", "Primer reintento tras (s):");
+        es.insert("saved-to", "Guardado en:");
+        es.insert("open-file", "Abrir");
+        es.insert("show-in-folder", "Mostrar en carpeta");
+        es.insert("fetch-info", "Obtener información");
+        es.insert("views-label", "vistas");
+        es.insert("estimated-size", "Tamaño estimado:");
+        es.insert("choose-format", "Elegir formato…");
+        es.insert("format-chooser-title", "Elegir formato");
+        es.insert("format-chooser-hint", "Elige una pista de vídeo y una de audio, o una sola pista que tenga ambas");
+        es.insert("custom-format-label", "Formato:");
+        es.insert("reset-format", "Restablecer");
+        es.insert("use-format", "Usar este formato");
+        es.insert("audio-only", "solo audio");
+        es.insert("format-column-id", "ID");
+        es.insert("format-column-ext", "Contenedor");
+        es.insert("format-column-resolution", "Resolución");
+        es.insert("format-column-fps", "FPS");
+        es.insert("format-column-vcodec", "Códec de vídeo");
+        es.insert("format-column-acodec", "Códec de audio");
+        es.insert("format-column-bitrate", "Tasa de bits");
+        es.insert("format-column-size", "Tamaño");
+        es.insert("playlist-detected", "Lista de reproducción detectada, elige las entradas a descargar");
+        es.insert("no-entries-selected", "No hay entradas seleccionadas");
+        es.insert("playlist-entries", "entradas");
+        es.insert("select-all", "Seleccionar todo");
+        es.insert("select-none", "No seleccionar nada");
+        es.insert("items-label", "Elementos");
+        es.insert("select-range", "Seleccionar rango");
+        es.insert("enqueue-selected", "Añadir seleccionados a la cola");
+        es.insert("item-label", "Elemento");
+        es.insert("total-label", "total");
+        es.insert("tab-downloads", "Descargas");
+        es.insert("tab-subscriptions", "Suscripciones");
+        es.insert("add-subscription", "Añadir suscripción");
+        es.insert("subscription-url", "URL del canal o lista");
+        es.insert("subscription-url-hint", "p. ej. la página de vídeos de un canal");
+        es.insert("subscription-name", "Nombre");
+        es.insert("filter-title", "El título contiene");
+        es.insert("filter-duration", "Duración (minutos, 0 = cualquiera)");
+        es.insert("filter-max-items", "Entradas más recientes a comprobar");
+        es.insert("skip-existing", "Descargar solo lo que se publique a partir de ahora");
+        es.insert("add-button", "Añadir");
+        es.insert("since-last-check", "Desde la última comprobación:");
+        es.insert("never-checked", "Aún no comprobada");
+        es.insert("new-videos", "vídeos nuevos");
+        es.insert("checking", "Comprobando...");
+        es.insert("check-now", "Comprobar ahora");
+        es.insert("check-interval", "Comprobar cada (minutos):");
+        es.insert("tab-archive", "Archivo");
+        es.insert("archive-description", "Los vídeos de esta lista se omiten en todas las descargas. Quita uno para volver a descargarlo.");
+        es.insert("archive-search", "Buscar por sitio o ID de vídeo");
+        es.insert("import-button", "Importar...");
+        es.insert("export-button", "Exportar...");
+        es.insert("reload-button", "Recargar");
+        es.insert("archive-entries", "entradas");
+        es.insert("archive-imported", "Entradas importadas:");
+        es.insert("archive-exported", "Archivo exportado a");
+        es.insert("tab-history", "Historial");
+        es.insert("outcome-completed", "Completada");
+        es.insert("outcome-failed", "Fallida");
+        es.insert("outcome-cancelled", "Cancelada");
+        es.insert("period-any", "Cualquier fecha");
+        es.insert("period-day", "Últimas 24 horas");
+        es.insert("period-week", "Últimos 7 días");
+        es.insert("period-month", "Últimos 30 días");
+        es.insert("filter-all", "Todos");
+        es.insert("history-search", "Buscar URL, título, sitio, ruta o error");
+        es.insert("download-again", "Descargar de nuevo");
+        es.insert("edit-and-download", "Cambiar ajustes…");
+        es.insert("delete-button", "Eliminar");
+        es.insert("export-json", "Exportar JSON...");
+        es.insert("export-csv", "Exportar CSV...");
+        es.insert("import-json", "Importar JSON...");
+        es.insert("records-exported", "Registros exportados a");
+        es.insert("records-imported", "Registros importados:");
+        es.insert("template-title", "Plantilla de nombre de archivo");
+        es.insert("template-insert", "Insertar:");
+        es.insert("template-reset", "Restablecer");
+        es.insert("template-preview", "Se guardará como:");
+        es.insert("template-preview-hint", "Obtén la información de una URL para ver el nombre del archivo");
+        es.insert("collision-label", "Si el archivo existe:");
+        es.insert("collision-skip", "Omitir");
+        es.insert("collision-overwrite", "Sobrescribir");
+        es.insert("collision-rename", "Guardar con otro nombre");
+        es.insert("status-already-present", "Ya existe, omitido");
+        es.insert("collision-kept", "Se conservó el archivo existente:");
+        es.insert("collision-replaced", "Se reemplazó el archivo existente:");
+        es.insert("collision-renamed", "Nombre en uso, guardado como:");
+        es.insert("subtitles-title", "Subtítulos");
+        es.insert("subtitles-automatic", "Incluir subtítulos automáticos");
+        es.insert("subtitles-none", "No hay subtítulos para esta URL");
+        es.insert("subtitles-automatic-list", "Subtítulos automáticos:");
+        es.insert("subtitles-hint", "Obtén la información de una URL para ver sus idiomas de subtítulos");
+        es.insert("subtitles-selected", "Idiomas:");
+        es.insert("subtitles-sidecar", "Archivos separados");
+        es.insert("subtitles-embed", "Incrustar en el MP4");
+        es.insert("subtitles-format", "Formato de archivo:");
+        es.insert("subtitles-mp3", "Los archivos MP3 no admiten subtítulos, así que se guardan como archivos separados");
+        es.insert("embed-title", "Portada, etiquetas y capítulos");
+        es.insert("embed-thumbnail", "Incrustar la miniatura como portada");
+        es.insert("embed-metadata", "Escribir título, artista, álbum, fecha y descripción");
+        es.insert("embed-chapters", "Incrustar capítulos");
+        es.insert("embed-ffmpeg-missing", "No se encontró ffmpeg, así que las descargas MP3 fallarán y las demás se guardarán sin esto ni subtítulos incrustados o convertidos. Instala ffmpeg (p. ej. sudo apt install ffmpeg) para usarlo.");
+        es.insert("embed-recheck", "Comprobar de nuevo");
+        es.insert("embed-skipped", "No se encontró ffmpeg, así que no se incrustó, convirtió ni dividió nada");
+        es.insert("split-chapters", "Guardar además cada capítulo como un archivo");
+        es.insert("chapters-title", "Capítulos");
+        es.insert("file-missing", "El archivo se movió o se eliminó");
+        es.insert("archive-locked", "Las entradas se pueden quitar cuando terminen las descargas en curso.");
+        es.insert("template-preview-unavailable", "Vista previa no disponible");
+        es.insert("collision-more", "más");
+        es.insert("collision-renamed-id", "Nombre en uso, guardado con su id:");
+        es.insert("waiting-for-info", "Obteniendo información, la descarga empezará cuando llegue");
         translations.insert("es-ES", es);
 
         // Get system language
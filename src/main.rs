@@ -1,9 +1,18 @@
-use eframe;
-
 mod app;
+mod archive;
+mod desktop;
 mod download;
+mod errors;
+mod events;
+mod history;
 mod localizations;
 mod models;
+mod progress;
+mod queue;
+mod records;
+mod storage;
+mod subscriptions;
+mod template;
 mod theme;
 mod ui;
 
@@ -12,7 +21,7 @@ use app::YtdlApp;
 fn main() -> eframe::Result<()> {
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
-            .with_inner_size([600.0, 500.0])
+            .with_inner_size([640.0, 640.0])
             .with_min_inner_size([400.0, 400.0]),
         ..Default::default()
     };
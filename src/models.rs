@@ -1,28 +1,855 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use crate::errors::ErrorKind;
+use crate::events::Stage;
+use crate::progress::batch_progress;
+use crate::storage::unix_now;
+use crate::history::{History, HistoryFilter};
+use crate::queue::DownloadQueue;
+use crate::subscriptions::{
+    SubscriptionForm, SubscriptionId, SubscriptionList, MAX_CHECK_INTERVAL_MINUTES, MIN_CHECK_INTERVAL_MINUTES,
+};
+use crate::template::DEFAULT_OUTPUT_TEMPLATE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum DownloadFormat {
+    #[default]
     MP4,
     MP3,
 }
 
-impl Default for DownloadFormat {
+pub type JobId = u64;
+
+// One entry of yt-dlp's `formats` list
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct FormatInfo {
+    pub format_id: String,
+    pub ext: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    // e.g. "1920x1080" or "audio only"
+    pub resolution: Option<String>,
+    pub fps: Option<f64>,
+    pub vcodec: Option<String>,
+    pub acodec: Option<String>,
+    // Total bitrate in kbit/s
+    pub tbr: Option<f64>,
+    pub filesize: Option<f64>,
+    pub filesize_approx: Option<f64>,
+}
+
+impl FormatInfo {
+    // yt-dlp uses "none" for a stream that lacks video or audio
+    pub fn has_video(&self) -> bool {
+        self.vcodec.as_deref().is_some_and(|codec| codec != "none")
+    }
+
+    pub fn has_audio(&self) -> bool {
+        self.acodec.as_deref().is_some_and(|codec| codec != "none")
+    }
+
+    pub fn size(&self) -> Option<u64> {
+        self.filesize.or(self.filesize_approx).map(|size| size as u64)
+    }
+}
+
+// Streams picked in the format chooser. A format that carries both video
+// and audio is stored as the video stream with no separate audio
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FormatSelection {
+    pub video: Option<String>,
+    pub audio: Option<String>,
+}
+
+impl FormatSelection {
+    // Format spec for yt-dlp's `-f`, or None when nothing is picked
+    pub fn spec(&self) -> Option<String> {
+        match (&self.video, &self.audio) {
+            (Some(video), Some(audio)) => Some(format!("{}+{}", video, audio)),
+            (Some(id), None) | (None, Some(id)) => Some(id.clone()),
+            (None, None) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FormatColumn {
+    Id,
+    Ext,
+    #[default]
+    Resolution,
+    Fps,
+    VideoCodec,
+    AudioCodec,
+    Bitrate,
+    Size,
+}
+
+// State of the "Choose format…" dialog
+#[derive(Debug, Clone, Default)]
+pub struct FormatChooser {
+    pub open: bool,
+    pub sort_by: FormatColumn,
+    pub descending: bool,
+    pub selection: FormatSelection,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ThumbnailInfo {
+    pub url: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SubtitleTrack {
+    pub ext: String,
+    // Human readable language, e.g. "English"
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Chapter {
+    // Seconds from the start
+    pub start_time: f64,
+    pub end_time: f64,
+    pub title: Option<String>,
+}
+
+// What `yt-dlp -J` reports about a single video
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct VideoMetadata {
+    pub id: String,
+    pub title: String,
+    pub uploader: Option<String>,
+    pub channel: Option<String>,
+    // Seconds
+    pub duration: Option<f64>,
+    // YYYYMMDD
+    pub upload_date: Option<String>,
+    pub view_count: Option<u64>,
+    pub thumbnail: Option<String>,
+    pub thumbnails: Vec<ThumbnailInfo>,
+    // Ordered from worst to best, as yt-dlp lists them
+    pub formats: Vec<FormatInfo>,
+    pub extractor_key: Option<String>,
+    pub webpage_url: Option<String>,
+    // Tracks by language code, for uploaded subtitles and generated captions
+    pub subtitles: HashMap<String, Vec<SubtitleTrack>>,
+    pub automatic_captions: HashMap<String, Vec<SubtitleTrack>>,
+    pub chapters: Vec<Chapter>,
+    // Every other field, for resolving output templates
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl VideoMetadata {
+    // Language codes with their names, sorted by code
+    pub fn subtitle_languages(&self, automatic: bool) -> Vec<(String, Option<String>)> {
+        let tracks = if automatic {
+            &self.automatic_captions
+        } else {
+            &self.subtitles
+        };
+        let mut languages: Vec<(String, Option<String>)> = tracks
+            .iter()
+            .map(|(code, tracks)| {
+                let name = tracks.iter().find_map(|track| track.name.clone());
+                (code.clone(), name)
+            })
+            .collect();
+        languages.sort();
+        languages
+    }
+
+    pub fn format(&self, format_id: &str) -> Option<&FormatInfo> {
+        self.formats.iter().find(|f| f.format_id == format_id)
+    }
+
+    // Combined size of the streams picked in the format chooser
+    pub fn selection_size(&self, selection: &FormatSelection) -> Option<u64> {
+        let mut total = 0;
+        for id in [&selection.video, &selection.audio].into_iter().flatten() {
+            total += self.format(id)?.size()?;
+        }
+        (total > 0).then_some(total)
+    }
+
+    pub fn uploader_name(&self) -> Option<&str> {
+        self.uploader.as_deref().or(self.channel.as_deref())
+    }
+
+    // Upload date as YYYY-MM-DD
+    pub fn formatted_upload_date(&self) -> Option<String> {
+        let date = self.upload_date.as_deref()?;
+        if date.len() != 8 || !date.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        Some(format!("{}-{}-{}", &date[..4], &date[4..6], &date[6..]))
+    }
+
+    // Largest thumbnail in a format the preview can decode; yt-dlp lists
+    // thumbnails in ascending preference
+    pub fn thumbnail_url(&self) -> Option<&str> {
+        let decodable = |url: &str| {
+            let path = url.split('?').next().unwrap_or(url);
+            [".jpg", ".jpeg", ".png", ".webp"]
+                .iter()
+                .any(|ext| path.ends_with(ext))
+        };
+        self.thumbnails
+            .iter()
+            .rev()
+            .map(|thumbnail| thumbnail.url.as_str())
+            .find(|url| decodable(url))
+            .or(self.thumbnail.as_deref())
+    }
+
+    // Size of the streams the given format would pick, mirroring the
+    // selectors passed to yt-dlp
+    pub fn estimated_size(&self, format: DownloadFormat) -> Option<u64> {
+        let best = |matches: &dyn Fn(&FormatInfo) -> bool| {
+            self.formats.iter().rev().find(|f| matches(f) && f.size().is_some())
+        };
+        let is_ext = |f: &FormatInfo, ext: &str| f.ext.as_deref() == Some(ext);
+
+        match format {
+            DownloadFormat::MP3 => best(&|f| f.has_audio() && !f.has_video()).and_then(FormatInfo::size),
+            DownloadFormat::MP4 => {
+                let video = best(&|f| f.has_video() && !f.has_audio() && is_ext(f, "mp4"));
+                let audio = best(&|f| f.has_audio() && !f.has_video() && is_ext(f, "m4a"));
+                match (video, audio) {
+                    (Some(video), Some(audio)) => Some(video.size()? + audio.size()?),
+                    _ => best(&|f| f.has_video() && f.has_audio() && is_ext(f, "mp4"))
+                        .and_then(FormatInfo::size),
+                }
+            }
+        }
+    }
+}
+
+// One entry of a playlist read with `--flat-playlist`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PlaylistEntry {
+    pub id: String,
+    // Extractor that handles the entry, used for download archive keys
+    pub ie_key: Option<String>,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    // Seconds
+    pub duration: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PlaylistInfo {
+    pub id: String,
+    pub title: Option<String>,
+    pub uploader: Option<String>,
+    pub entries: Vec<PlaylistEntry>,
+}
+
+// Checklist built from a playlist URL
+pub struct PlaylistPreview {
+    pub info: PlaylistInfo,
+    pub selected: Vec<bool>,
+    // 1-based, inclusive item range for the "Select range" shortcut
+    pub range_start: usize,
+    pub range_end: usize,
+}
+
+impl PlaylistPreview {
+    pub fn new(info: PlaylistInfo) -> Self {
+        let count = info.entries.len();
+        Self {
+            info,
+            selected: vec![true; count],
+            range_start: 1,
+            range_end: count.max(1),
+        }
+    }
+
+    // Entries ticked in the checklist that yt-dlp gave a URL for
+    pub fn selected_entries(&self) -> impl Iterator<Item = &PlaylistEntry> {
+        self.info
+            .entries
+            .iter()
+            .zip(&self.selected)
+            .filter(|(entry, selected)| **selected && entry.url.is_some())
+            .map(|(entry, _)| entry)
+    }
+}
+
+// Result of the "Fetch info" step for the URL currently entered
+pub struct Preview {
+    pub url: String,
+    pub loading: bool,
+    // Loading, but yt-dlp is only started once an update of it is done
+    pub deferred: bool,
+    // Download was clicked while loading; a video is queued once its info
+    // arrives, a playlist waits for its entries to be picked
+    pub start_when_ready: bool,
+    pub metadata: Option<VideoMetadata>,
+    // Set instead of `metadata` when the URL is a playlist
+    pub playlist: Option<PlaylistPreview>,
+    pub thumbnail: Option<egui::TextureHandle>,
+    pub error: Option<String>,
+    pub chooser: FormatChooser,
+    // Streams applied from the chooser; replace the preset selector of the
+    // chosen format
+    pub selected_format: Option<FormatSelection>,
+}
+
+// What yt-dlp reports about a file it finished, one JSON line per file
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DownloadedFile {
+    pub filepath: PathBuf,
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub extractor_key: Option<String>,
+    // Seconds of media
+    #[serde(default)]
+    pub duration: Option<f64>,
+    #[serde(default)]
+    pub chapters: Vec<Chapter>,
+    // Position of the item in a playlist run, as in "Downloading item N"
+    #[serde(default)]
+    pub playlist_autonumber: Option<u64>,
+}
+
+// yt-dlp options beyond the URL, format and directory of a job
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DownloadOptions {
+    pub cookies_file: Option<String>,
+    pub format_spec: Option<String>,
+    pub download_archive: Option<PathBuf>,
+    pub output_template: Option<String>,
+    pub collision_policy: CollisionPolicy,
+    // Video ids the run is limited to; every item when empty
+    pub only_ids: Vec<String>,
+    // None when no subtitle languages are picked
+    pub subtitles: Option<SubtitleSettings>,
+    // Whether sidecar subtitles are converted to the picked format, which
+    // needs ffmpeg
+    pub convert_subtitles: bool,
+    pub embed: EmbedSettings,
+    pub split_chapters: bool,
+}
+
+// What a job does when the file it would write already exists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CollisionPolicy {
+    // Leave the existing file alone and mark the job as already present
+    #[default]
+    Skip,
+    Overwrite,
+    // Save next to it under the video id or a numeric suffix
+    Rename,
+}
+
+// How a job dealt with an existing file, holding that file's path
+#[derive(Debug, Clone, PartialEq)]
+pub enum Collision {
+    AlreadyPresent(PathBuf),
+    Overwritten(PathBuf),
+    Renamed(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum JobStatus {
+    Queued,
+    Downloading,
+    Paused,
+    Completed,
+    Cancelled,
+    Failed(String),
+}
+
+pub struct DownloadJob {
+    pub id: JobId,
+    pub url: String,
+    // Known once metadata was fetched for the URL or the download finished
+    pub title: Option<String>,
+    pub extractor: Option<String>,
+    pub media_id: Option<String>,
+    // Seconds of media
+    pub duration: Option<f64>,
+    pub format: DownloadFormat,
+    // Streams picked by hand, passed to yt-dlp as is
+    pub format_spec: Option<String>,
+    pub download_dir: String,
+    // Subscription whose check queued this job
+    pub subscription: Option<SubscriptionId>,
+    pub status: JobStatus,
+    // Whole-job percentage across all stages
+    pub progress: f32,
+    // Percentage of the current stage alone
+    pub stage_progress: f32,
+    pub downloaded_bytes: Option<u64>,
+    pub total_bytes: Option<u64>,
+    pub total_is_estimate: bool,
+    pub fragments: Option<(u64, u64)>,
+    // Bytes per second
+    pub speed: Option<f64>,
+    // Seconds remaining
+    pub eta: Option<u64>,
+    pub stage: Option<Stage>,
+    // Position and item count when one run downloads a whole playlist;
+    // `progress` then covers every item and `item_progress` the current one
+    pub item: Option<(u64, u64)>,
+    pub item_progress: f32,
+    // When yt-dlp was last started and the progress the job had then
+    pub run_started: Option<(Instant, f32)>,
+    // The last file the job produced, and every one of them for playlists
+    pub output_path: Option<PathBuf>,
+    pub output_paths: Vec<PathBuf>,
+    // Existing files met while downloading, see CollisionPolicy
+    pub collisions: Vec<Collision>,
+    // Video ids and paths of items left out because their name was taken
+    pub taken: Vec<(String, PathBuf)>,
+    // `-o` replacing the template once the job was renamed
+    pub output_name: Option<String>,
+    // Items a renamed run is limited to; every item when empty
+    pub only_ids: Vec<String>,
+    // Downloaded again from the history, so the archive must not skip it
+    pub ignore_archive: bool,
+    // Taken from the settings when the job is added; None when no
+    // languages were picked
+    pub subtitles: Option<SubtitleSettings>,
+    pub embed: EmbedSettings,
+    pub split_chapters: bool,
+    // `-o` template, collision policy and cookies file, also taken from
+    // the settings when the job is added
+    pub output_template: String,
+    pub collision_policy: CollisionPolicy,
+    pub cookies_file: Option<String>,
+    // Set alongside JobStatus::Failed
+    pub error_kind: Option<ErrorKind>,
+    // Number of runs of this job that failed since it was added or last
+    // retried by hand
+    pub attempts: u32,
+    // When a queued job waiting after a transient failure may start again
+    pub retry_at: Option<Instant>,
+    // Message of the transient failure a pending retry is waiting out
+    pub last_failure: Option<String>,
+    // Whether yt-dlp was already updated once to retry this job
+    pub updated_for_retry: bool,
+    // Unix times the job was added and first started
+    pub queued_at: u64,
+    pub started_at: Option<u64>,
+    // Whether the final state was written to the history; cleared on retry
+    pub in_history: bool,
+    // Non-fatal yt-dlp warnings, without duplicates
+    pub warnings: Vec<String>,
+    // Files yt-dlp announced as destinations, used to clean up after a
+    // paused job is cancelled
+    pub destinations: Vec<PathBuf>,
+    // Those of them that are only streams to merge, removed whole
+    pub intermediates: Vec<PathBuf>,
+}
+
+impl DownloadJob {
+    pub fn new(id: JobId, url: String, format: DownloadFormat, download_dir: String) -> Self {
+        Self {
+            id,
+            url,
+            title: None,
+            extractor: None,
+            media_id: None,
+            duration: None,
+            format,
+            format_spec: None,
+            download_dir,
+            subscription: None,
+            status: JobStatus::Queued,
+            progress: 0.0,
+            stage_progress: 0.0,
+            downloaded_bytes: None,
+            total_bytes: None,
+            total_is_estimate: false,
+            fragments: None,
+            speed: None,
+            eta: None,
+            stage: None,
+            item: None,
+            item_progress: 0.0,
+            run_started: None,
+            output_path: None,
+            output_paths: Vec::new(),
+            error_kind: None,
+            attempts: 0,
+            retry_at: None,
+            last_failure: None,
+            updated_for_retry: false,
+            queued_at: unix_now(),
+            started_at: None,
+            in_history: false,
+            collisions: Vec::new(),
+            taken: Vec::new(),
+            output_name: None,
+            only_ids: Vec::new(),
+            ignore_archive: false,
+            subtitles: None,
+            embed: EmbedSettings::default(),
+            split_chapters: false,
+            output_template: DEFAULT_OUTPUT_TEMPLATE.to_string(),
+            collision_policy: CollisionPolicy::default(),
+            cookies_file: None,
+            warnings: Vec::new(),
+            destinations: Vec::new(),
+            intermediates: Vec::new(),
+        }
+    }
+
+    // Copies the settings a job keeps from the moment it is added, so
+    // changing them later only affects jobs added afterwards
+    pub fn capture_settings(&mut self, settings: &Settings) {
+        self.subtitles = Some(settings.subtitles.clone())
+            .filter(|subtitles| !subtitles.languages.is_empty());
+        self.embed = settings.embed;
+        self.split_chapters = settings.split_chapters;
+        self.output_template = settings.output_template.clone();
+        self.collision_policy = settings.collision_policy;
+        self.cookies_file = settings.cookies_file.clone();
+    }
+
+    // Clears transfer figures that only make sense while yt-dlp is running
+    pub fn clear_transfer_stats(&mut self) {
+        self.speed = None;
+        self.eta = None;
+        self.stage = None;
+    }
+
+    // Sets the progress of the current item, which is the whole job unless
+    // the run covers several items
+    pub fn set_item_progress(&mut self, percent: f32) {
+        self.item_progress = percent;
+        self.progress = match self.item {
+            Some((index, count)) => batch_progress(index, count, percent),
+            None => percent,
+        };
+    }
+
+    // Time left for the whole run, extrapolated from the progress made since
+    // yt-dlp was started
+    pub fn batch_eta(&self) -> Option<Duration> {
+        let (started, start_progress) = self.run_started?;
+        let done = self.progress - start_progress;
+        if done <= 0.0 {
+            return None;
+        }
+        let elapsed = started.elapsed().as_secs_f32();
+        Some(Duration::from_secs_f32(elapsed * (100.0 - self.progress) / done))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.status,
+            JobStatus::Completed | JobStatus::Cancelled | JobStatus::Failed(_)
+        )
+    }
+}
+
+// Unfinished job as written to the config dir so it survives a restart or crash
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersistedJob {
+    pub url: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub extractor: Option<String>,
+    #[serde(default)]
+    pub media_id: Option<String>,
+    pub format: DownloadFormat,
+    #[serde(default)]
+    pub format_spec: Option<String>,
+    pub download_dir: String,
+    #[serde(default)]
+    pub subscription: Option<SubscriptionId>,
+    pub status: JobStatus,
+    #[serde(default)]
+    pub destinations: Vec<PathBuf>,
+    #[serde(default)]
+    pub intermediates: Vec<PathBuf>,
+    // A rename in progress, so the restored job keeps the name it chose
+    #[serde(default)]
+    pub output_name: Option<String>,
+    #[serde(default)]
+    pub only_ids: Vec<String>,
+    #[serde(default)]
+    pub ignore_archive: bool,
+    #[serde(default)]
+    pub subtitles: Option<SubtitleSettings>,
+    #[serde(default)]
+    pub embed: EmbedSettings,
+    #[serde(default)]
+    pub split_chapters: bool,
+    #[serde(default = "default_output_template")]
+    pub output_template: String,
+    #[serde(default)]
+    pub collision_policy: CollisionPolicy,
+    #[serde(default)]
+    pub cookies_file: Option<String>,
+}
+
+fn default_output_template() -> String {
+    DEFAULT_OUTPUT_TEMPLATE.to_string()
+}
+
+impl From<&DownloadJob> for PersistedJob {
+    fn from(job: &DownloadJob) -> Self {
+        Self {
+            url: job.url.clone(),
+            title: job.title.clone(),
+            extractor: job.extractor.clone(),
+            media_id: job.media_id.clone(),
+            format: job.format,
+            format_spec: job.format_spec.clone(),
+            download_dir: job.download_dir.clone(),
+            subscription: job.subscription,
+            status: job.status.clone(),
+            destinations: job.destinations.clone(),
+            intermediates: job.intermediates.clone(),
+            output_name: job.output_name.clone(),
+            only_ids: job.only_ids.clone(),
+            ignore_archive: job.ignore_archive,
+            subtitles: job.subtitles.clone(),
+            embed: job.embed,
+            split_chapters: job.split_chapters,
+            output_template: job.output_template.clone(),
+            collision_policy: job.collision_policy,
+            cookies_file: job.cookies_file.clone(),
+        }
+    }
+}
+
+pub const MAX_RETRY_ATTEMPTS: u32 = 10;
+pub const MAX_RETRY_DELAY_SECS: u64 = 120;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    // Attempts in total, including the first one
+    pub max_attempts: u32,
+    // Wait before the first retry; each further retry doubles it
+    pub base_delay_secs: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_secs: 5,
+        }
+    }
+}
+
+impl RetryPolicy {
+    // Delay before the next try after `attempts` failed ones, or None once
+    // the policy is exhausted
+    pub fn next_delay(&self, attempts: u32) -> Option<Duration> {
+        if attempts >= self.max_attempts {
+            return None;
+        }
+        let factor = 2u64.saturating_pow(attempts.saturating_sub(1));
+        Some(Duration::from_secs(self.base_delay_secs.saturating_mul(factor)))
+    }
+}
+
+pub const MAX_PARALLEL_DOWNLOADS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleFormat {
+    #[default]
+    Srt,
+    Vtt,
+    Ass,
+}
+
+impl SubtitleFormat {
+    pub fn ext(self) -> &'static str {
+        match self {
+            SubtitleFormat::Srt => "srt",
+            SubtitleFormat::Vtt => "vtt",
+            SubtitleFormat::Ass => "ass",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubtitleSettings {
+    // Language codes as yt-dlp lists them, e.g. "en" or "pt-BR"
+    pub languages: Vec<String>,
+    // Also fetch generated captions for languages without subtitles
+    pub automatic: bool,
+    // Format of sidecar files; embedded tracks are converted by yt-dlp
+    pub format: SubtitleFormat,
+    // Embed into MP4 downloads instead of writing files next to them
+    pub embed: bool,
+}
+
+// What gets written into downloaded files besides the media itself. All of
+// it needs ffmpeg
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmbedSettings {
+    // Thumbnail as cover art
+    pub thumbnail: bool,
+    // Title, artist, album, date and description tags
+    pub metadata: bool,
+    pub chapters: bool,
+}
+
+impl EmbedSettings {
+    pub fn any(self) -> bool {
+        self.thumbnail || self.metadata || self.chapters
+    }
+}
+
+// Saved to the config dir whenever it changes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    // How many yt-dlp processes may run at once
+    pub max_concurrent: usize,
+    // Remove `.part`/`.ytdl` and fragment files left by a cancelled job
+    pub delete_partial_on_cancel: bool,
+    // Netscape-format cookies file passed to yt-dlp for private and
+    // age-restricted videos
+    pub cookies_file: Option<String>,
+    pub retry: RetryPolicy,
+    // How often subscriptions are checked for new entries
+    pub check_interval_minutes: u64,
+    // yt-dlp `-o` template for file names, relative to the download directory
+    pub output_template: String,
+    // What to do when that name is already taken
+    pub collision_policy: CollisionPolicy,
+    pub subtitles: SubtitleSettings,
+    pub embed: EmbedSettings,
+    // Also save one file per chapter
+    pub split_chapters: bool,
+}
+
+impl Default for Settings {
     fn default() -> Self {
-        Self::MP4
+        Self {
+            max_concurrent: 2,
+            delete_partial_on_cancel: true,
+            cookies_file: None,
+            retry: RetryPolicy::default(),
+            check_interval_minutes: 60,
+            output_template: DEFAULT_OUTPUT_TEMPLATE.to_string(),
+            collision_policy: CollisionPolicy::default(),
+            subtitles: SubtitleSettings::default(),
+            embed: EmbedSettings::default(),
+            split_chapters: false,
+        }
     }
 }
 
+impl Settings {
+    // Brings values edited by hand in the settings file back within what
+    // the settings panel allows
+    pub fn clamp(&mut self) {
+        self.max_concurrent = self.max_concurrent.clamp(1, MAX_PARALLEL_DOWNLOADS);
+        self.retry.max_attempts = self.retry.max_attempts.clamp(1, MAX_RETRY_ATTEMPTS);
+        self.retry.base_delay_secs = self.retry.base_delay_secs.clamp(1, MAX_RETRY_DELAY_SECS);
+        self.check_interval_minutes = self
+            .check_interval_minutes
+            .clamp(MIN_CHECK_INTERVAL_MINUTES, MAX_CHECK_INTERVAL_MINUTES);
+        if self.output_template.trim().is_empty() {
+            self.output_template = DEFAULT_OUTPUT_TEMPLATE.to_string();
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tab {
+    #[default]
+    Downloads,
+    Subscriptions,
+    History,
+    Archive,
+}
+
+// What the Archive tab shows; reloaded from disk whenever the tab is opened
+#[derive(Default)]
+pub struct ArchiveView {
+    pub entries: Vec<String>,
+    pub search: String,
+    // Outcome of the last import, export or removal
+    pub message: Option<String>,
+    // Set while downloads run. yt-dlp appends to the archive as it goes and
+    // a removal rewrites the file, which would drop those lines
+    pub locked: bool,
+}
+
 #[derive(Default)]
 pub struct AppState {
+    pub tab: Tab,
     pub url: String,
     pub format: DownloadFormat,
-    pub is_downloading: bool,
-    pub progress: f32,
+    pub download_dir: String,
+    pub queue: DownloadQueue,
+    pub settings: Settings,
+    // Jobs left unfinished by the previous run, waiting for the user to
+    // resume or discard them
+    pub recovered_jobs: Vec<PersistedJob>,
+    pub subscriptions: SubscriptionList,
+    pub subscription_form: SubscriptionForm,
+    pub archive: ArchiveView,
+    pub history: History,
+    pub history_filter: HistoryFilter,
+    // Outcome of the last export or import
+    pub history_message: Option<String>,
+    // URL loaded from the history to change its settings; once queued it is
+    // downloaded even though the archive lists it
+    pub rerun_url: Option<String>,
+    // Template being edited; copied to the settings while it is valid
+    pub template_draft: String,
+    // Character position of the cursor in the template editor
+    pub template_cursor: Option<usize>,
+    // Whether ffmpeg was found on the PATH at the last check
+    pub ffmpeg_available: bool,
+    pub preview: Option<Preview>,
+    pub is_updating: bool,
+    // An update was requested while downloads were running
+    pub update_pending: bool,
+    // Bytes of the new yt-dlp binary received so far and its total size
+    pub updater_progress: Option<(u64, Option<u64>)>,
     pub status: String,
     pub error: Option<String>,
     pub last_error: Option<String>,
-    pub download_speed: String,
-    pub eta: String,
-    pub output_path: Option<PathBuf>,
-    pub download_dir: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_doubles_until_attempts_run_out() {
+        let policy = RetryPolicy {
+            max_attempts: 4,
+            base_delay_secs: 5,
+        };
+        assert_eq!(policy.next_delay(1), Some(Duration::from_secs(5)));
+        assert_eq!(policy.next_delay(2), Some(Duration::from_secs(10)));
+        assert_eq!(policy.next_delay(3), Some(Duration::from_secs(20)));
+        assert_eq!(policy.next_delay(4), None);
+    }
 }
@@ -0,0 +1,318 @@
+use serde::Deserialize;
+
+use crate::events::{DownloadEvent, Stage};
+use crate::models::DownloadFormat;
+
+// Marks our progress lines so they can be told apart from yt-dlp's own output
+const PROGRESS_MARKER: &str = "[ytdl-gui:progress]";
+
+// Passed to `--progress-template`; prints yt-dlp's progress dict as JSON
+// together with the codecs of the stream being downloaded, which tell the
+// video and audio halves of a merged format apart, and the item's position
+// when one run downloads a whole playlist
+pub const PROGRESS_TEMPLATE: &str = concat!(
+    "download:[ytdl-gui:progress] {",
+    r#""progress": %(progress)j, "#,
+    r#""vcodec": %(info.vcodec|null)j, "#,
+    r#""acodec": %(info.acodec|null)j, "#,
+    r#""playlist_index": %(info.playlist_index|null)j, "#,
+    r#""playlist_count": %(info.playlist_count|null)j"#,
+    "}"
+);
+
+// Post-processor tags yt-dlp prefixes its output with, and the stage each
+// one belongs to
+const POSTPROCESSOR_STAGES: [(&str, Stage); 14] = [
+    ("[Merger]", Stage::Merging),
+    ("[ExtractAudio]", Stage::ExtractingAudio),
+    ("[EmbedThumbnail]", Stage::Embedding),
+    ("[EmbedSubtitle]", Stage::Embedding),
+    ("[Metadata]", Stage::Embedding),
+    ("[MoveFiles]", Stage::Moving),
+    ("[FixupM3u8]", Stage::PostProcessing),
+    ("[FixupM4a]", Stage::PostProcessing),
+    ("[FixupStretched]", Stage::PostProcessing),
+    ("[FixupDuplicateMoov]", Stage::PostProcessing),
+    ("[VideoConvertor]", Stage::PostProcessing),
+    ("[SubtitlesConvertor]", Stage::PostProcessing),
+    ("[ThumbnailsConvertor]", Stage::PostProcessing),
+    ("[SplitChapters]", Stage::PostProcessing),
+];
+
+// Fields of yt-dlp's progress dict we use. Every field is optional since
+// yt-dlp omits or nulls them depending on the downloader, and numbers may
+// arrive as floats
+#[derive(Debug, Deserialize)]
+struct RawProgress {
+    status: Option<String>,
+    downloaded_bytes: Option<f64>,
+    total_bytes: Option<f64>,
+    total_bytes_estimate: Option<f64>,
+    speed: Option<f64>,
+    eta: Option<f64>,
+    fragment_index: Option<f64>,
+    fragment_count: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawProgressLine {
+    progress: RawProgress,
+    vcodec: Option<String>,
+    acodec: Option<String>,
+    playlist_index: Option<f64>,
+    playlist_count: Option<f64>,
+}
+
+// A progress line split into what the download thread forwards
+pub struct ProgressLine {
+    pub event: DownloadEvent,
+    pub stage: Stage,
+    // 1-based position and item count of a multi-item run
+    pub item: Option<(u64, u64)>,
+}
+
+// yt-dlp uses "none" for a stream that lacks video or audio
+fn has_codec(codec: &Option<String>) -> Option<bool> {
+    codec.as_deref().map(|codec| codec != "none")
+}
+
+// Parses a line produced by PROGRESS_TEMPLATE into a progress event, the
+// stage it belongs to and the playlist item it is for. Lines without the
+// marker or with malformed JSON are not progress and yield None
+pub fn parse_progress_line(line: &str) -> Option<ProgressLine> {
+    let json = line.trim().strip_prefix(PROGRESS_MARKER)?;
+    let line_data: RawProgressLine = match serde_json::from_str(json.trim()) {
+        Ok(raw) => raw,
+        Err(e) => {
+            log::warn!("Unreadable progress line {:?}: {}", line, e);
+            return None;
+        }
+    };
+    let raw = line_data.progress;
+
+    let stage = match (has_codec(&line_data.vcodec), has_codec(&line_data.acodec)) {
+        (Some(true), Some(false)) => Stage::Video,
+        (Some(false), Some(true)) => Stage::Audio,
+        _ => Stage::Downloading,
+    };
+
+    let as_count = |value: Option<f64>| value.filter(|v| v.is_finite() && *v >= 0.0).map(|v| v as u64);
+
+    let downloaded_bytes = as_count(raw.downloaded_bytes);
+    let exact_total = as_count(raw.total_bytes).filter(|&total| total > 0);
+    let estimated_total = as_count(raw.total_bytes_estimate).filter(|&total| total > 0);
+    let total_bytes = exact_total.or(estimated_total);
+    let fragments = match (as_count(raw.fragment_index), as_count(raw.fragment_count)) {
+        (Some(index), Some(count)) if count > 0 => Some((index, count)),
+        _ => None,
+    };
+
+    let percent = if raw.status.as_deref() == Some("finished") {
+        Some(100.0)
+    } else if let (Some(downloaded), Some(total)) = (downloaded_bytes, total_bytes) {
+        Some((downloaded as f32 / total as f32 * 100.0).min(100.0))
+    } else {
+        fragments.map(|(index, count)| (index as f32 / count as f32 * 100.0).min(100.0))
+    };
+
+    let item = match (as_count(line_data.playlist_index), as_count(line_data.playlist_count)) {
+        (Some(index), Some(count)) if index > 0 && count > 0 => Some((index, count)),
+        _ => None,
+    };
+
+    let event = DownloadEvent::Progress {
+        percent,
+        downloaded_bytes,
+        total_bytes,
+        total_is_estimate: exact_total.is_none() && estimated_total.is_some(),
+        speed: raw.speed.filter(|v| v.is_finite() && *v >= 0.0),
+        eta: as_count(raw.eta),
+        fragments,
+    };
+    Some(ProgressLine { event, stage, item })
+}
+
+// Position announced by yt-dlp before each entry of a playlist, as in
+// `[download] Downloading item 7 of 42` (`video` in older releases)
+pub fn parse_item_line(line: &str) -> Option<(u64, u64)> {
+    let rest = line.trim().strip_prefix("[download] Downloading ")?;
+    let mut words = rest.split_whitespace();
+    if !matches!(words.next(), Some("item" | "video")) {
+        return None;
+    }
+    let index = words.next()?.parse().ok()?;
+    if words.next() != Some("of") {
+        return None;
+    }
+    let count = words.next()?.parse().ok()?;
+    (index > 0 && count > 0).then_some((index, count))
+}
+
+// Stage announced by a post-processor output line such as
+// `[Merger] Merging formats into "..."`
+pub fn postprocessor_stage(line: &str) -> Option<Stage> {
+    POSTPROCESSOR_STAGES
+        .iter()
+        .find(|(tag, _)| line.starts_with(tag))
+        .map(|(_, stage)| *stage)
+}
+
+// Part of the whole job, in percent, each stage covers. Downloads take most
+// of the bar; post-processing only reports start and end so it gets a slice
+// at the end
+fn stage_range(stage: Stage, format: DownloadFormat) -> (f32, f32) {
+    match (stage, format) {
+        (Stage::Video, _) => (0.0, 75.0),
+        (Stage::Audio | Stage::Downloading, DownloadFormat::MP3) => (0.0, 90.0),
+        (Stage::Audio, DownloadFormat::MP4) => (75.0, 95.0),
+        (Stage::Downloading, DownloadFormat::MP4) => (0.0, 95.0),
+        (_, DownloadFormat::MP3) => (90.0, 100.0),
+        (_, DownloadFormat::MP4) => (95.0, 100.0),
+    }
+}
+
+// Combines the current stage and its own progress into one job percentage
+pub fn overall_progress(stage: Stage, stage_percent: f32, format: DownloadFormat) -> f32 {
+    let (start, end) = stage_range(stage, format);
+    start + (end - start) * stage_percent.clamp(0.0, 100.0) / 100.0
+}
+
+// Whole-run percentage when item `index` of `count` is `item_percent` done
+pub fn batch_progress(index: u64, count: u64, item_percent: f32) -> f32 {
+    let done = index.saturating_sub(1).min(count) as f32;
+    ((done * 100.0 + item_percent.clamp(0.0, 100.0)) / count.max(1) as f32).min(100.0)
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+pub fn format_speed(bytes_per_second: f64) -> String {
+    format!("{}/s", format_bytes(bytes_per_second as u64))
+}
+
+pub fn format_duration(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
+
+// Unix time as `YYYY-MM-DD HH:MM` in UTC
+pub fn format_timestamp(unix: u64) -> String {
+    let days = (unix / 86400) as i64;
+    let (hours, minutes) = (unix / 3600 % 24, unix / 60 % 60);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{}-{:02}-{:02} {:02}:{:02}", year, month, day, hours, minutes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(json: &str) -> ProgressLine {
+        parse_progress_line(&format!("{} {}", PROGRESS_MARKER, json)).expect("progress line")
+    }
+
+    #[test]
+    fn ignores_lines_without_the_marker() {
+        assert!(parse_progress_line("[download] Destination: video.mp4").is_none());
+        assert!(parse_progress_line(&format!("{} not json", PROGRESS_MARKER)).is_none());
+    }
+
+    #[test]
+    fn reads_bytes_speed_and_eta() {
+        let line = progress(
+            r#"{"progress": {"status": "downloading", "downloaded_bytes": 512, "total_bytes": 2048,
+                "speed": 1024.5, "eta": 3.0}, "vcodec": "avc1", "acodec": "mp4a",
+                "playlist_index": null, "playlist_count": null}"#,
+        );
+        assert_eq!(line.stage, Stage::Downloading);
+        assert_eq!(line.item, None);
+        assert_eq!(
+            line.event,
+            DownloadEvent::Progress {
+                percent: Some(25.0),
+                downloaded_bytes: Some(512),
+                total_bytes: Some(2048),
+                total_is_estimate: false,
+                speed: Some(1024.5),
+                eta: Some(3),
+                fragments: None,
+            }
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_estimate_and_fragments() {
+        let line = progress(
+            r#"{"progress": {"status": "downloading", "downloaded_bytes": 100,
+                "total_bytes_estimate": 400.0}, "vcodec": null, "acodec": null,
+                "playlist_index": null, "playlist_count": null}"#,
+        );
+        match line.event {
+            DownloadEvent::Progress { percent, total_bytes, total_is_estimate, .. } => {
+                assert_eq!(percent, Some(25.0));
+                assert_eq!(total_bytes, Some(400));
+                assert!(total_is_estimate);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        let line = progress(
+            r#"{"progress": {"status": "downloading", "fragment_index": 3, "fragment_count": 12},
+                "vcodec": null, "acodec": null, "playlist_index": null, "playlist_count": null}"#,
+        );
+        match line.event {
+            DownloadEvent::Progress { percent, fragments, .. } => {
+                assert_eq!(percent, Some(25.0));
+                assert_eq!(fragments, Some((3, 12)));
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn tells_video_and_audio_streams_apart() {
+        let video = progress(
+            r#"{"progress": {"status": "downloading"}, "vcodec": "vp9", "acodec": "none",
+                "playlist_index": 2, "playlist_count": 5}"#,
+        );
+        assert_eq!(video.stage, Stage::Video);
+        assert_eq!(video.item, Some((2, 5)));
+
+        let audio = progress(
+            r#"{"progress": {"status": "finished"}, "vcodec": "none", "acodec": "opus",
+                "playlist_index": null, "playlist_count": null}"#,
+        );
+        assert_eq!(audio.stage, Stage::Audio);
+        match audio.event {
+            DownloadEvent::Progress { percent, .. } => assert_eq!(percent, Some(100.0)),
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+}
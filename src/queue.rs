@@ -0,0 +1,192 @@
+use std::time::Instant;
+
+use crate::models::{DownloadFormat, DownloadJob, JobId, JobStatus, PersistedJob};
+
+// Ordered list of download jobs. Jobs keep their position for the lifetime
+// of the queue so the UI list stays stable while downloads progress.
+#[derive(Default)]
+pub struct DownloadQueue {
+    jobs: Vec<DownloadJob>,
+    next_id: JobId,
+}
+
+impl DownloadQueue {
+    pub fn enqueue(&mut self, url: String, format: DownloadFormat, download_dir: String) -> JobId {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.jobs.push(DownloadJob::new(id, url, format, download_dir));
+        id
+    }
+
+    // Re-adds a job from a previous run. It goes back in line, paused or
+    // not, and resumes its partial files
+    pub fn restore(&mut self, persisted: PersistedJob) -> JobId {
+        let id = self.enqueue(persisted.url, persisted.format, persisted.download_dir);
+        if let Some(job) = self.get_mut(id) {
+            job.title = persisted.title;
+            job.extractor = persisted.extractor;
+            job.media_id = persisted.media_id;
+            job.format_spec = persisted.format_spec;
+            job.subscription = persisted.subscription;
+            job.destinations = persisted.destinations;
+            job.intermediates = persisted.intermediates;
+            job.output_name = persisted.output_name;
+            job.only_ids = persisted.only_ids;
+            job.ignore_archive = persisted.ignore_archive;
+            job.subtitles = persisted.subtitles;
+            job.embed = persisted.embed;
+            job.split_chapters = persisted.split_chapters;
+            job.output_template = persisted.output_template;
+            job.collision_policy = persisted.collision_policy;
+            job.cookies_file = persisted.cookies_file;
+        }
+        id
+    }
+
+    // Jobs that still need to run, in the form written to disk
+    pub fn unfinished(&self) -> Vec<PersistedJob> {
+        self.jobs
+            .iter()
+            .filter(|job| !job.is_finished())
+            .map(PersistedJob::from)
+            .collect()
+    }
+
+    pub fn jobs(&self) -> &[DownloadJob] {
+        &self.jobs
+    }
+
+    pub fn get(&self, id: JobId) -> Option<&DownloadJob> {
+        self.jobs.iter().find(|job| job.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: JobId) -> Option<&mut DownloadJob> {
+        self.jobs.iter_mut().find(|job| job.id == id)
+    }
+
+    // Oldest job still waiting to be started, skipping jobs whose retry
+    // delay has not passed yet
+    pub fn next_queued(&self, now: Instant) -> Option<JobId> {
+        self.jobs
+            .iter()
+            .find(|job| {
+                job.status == JobStatus::Queued && job.retry_at.map_or(true, |at| at <= now)
+            })
+            .map(|job| job.id)
+    }
+
+    pub fn has_pending_retry(&self) -> bool {
+        self.jobs
+            .iter()
+            .any(|job| job.status == JobStatus::Queued && job.retry_at.is_some())
+    }
+
+    // Puts a failed or cancelled job back in line
+    pub fn retry(&mut self, id: JobId) {
+        if let Some(job) = self.get_mut(id) {
+            if matches!(job.status, JobStatus::Failed(_) | JobStatus::Cancelled) {
+                job.status = JobStatus::Queued;
+                job.error_kind = None;
+                job.attempts = 0;
+                job.retry_at = None;
+                job.last_failure = None;
+                job.updated_for_retry = false;
+                job.in_history = false;
+                job.collisions.clear();
+                job.taken.clear();
+                job.output_name = None;
+                job.only_ids.clear();
+                job.output_path = None;
+                job.output_paths.clear();
+                job.warnings.clear();
+            }
+        }
+    }
+
+    pub fn contains_url(&self, url: &str) -> bool {
+        self.jobs.iter().any(|job| job.url == url)
+    }
+
+    pub fn remove(&mut self, id: JobId) {
+        self.jobs.retain(|job| job.id != id || !job.is_finished());
+    }
+
+    pub fn clear_finished(&mut self) {
+        self.jobs.retain(|job| !job.is_finished());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn queue_of(count: usize) -> (DownloadQueue, Vec<JobId>) {
+        let mut queue = DownloadQueue::default();
+        let ids = (0..count)
+            .map(|i| queue.enqueue(format!("https://example.com/{}", i), DownloadFormat::MP4, "/tmp".to_string()))
+            .collect();
+        (queue, ids)
+    }
+
+    #[test]
+    fn starts_jobs_in_the_order_they_were_added() {
+        let (mut queue, ids) = queue_of(3);
+        assert_eq!(queue.next_queued(Instant::now()), Some(ids[0]));
+
+        queue.get_mut(ids[0]).unwrap().status = JobStatus::Completed;
+        assert_eq!(queue.next_queued(Instant::now()), Some(ids[1]));
+        assert!(queue.contains_url("https://example.com/2"));
+    }
+
+    #[test]
+    fn removes_only_finished_jobs() {
+        let (mut queue, ids) = queue_of(3);
+        queue.get_mut(ids[0]).unwrap().status = JobStatus::Completed;
+        queue.get_mut(ids[1]).unwrap().status = JobStatus::Downloading;
+
+        queue.remove(ids[1]);
+        assert_eq!(queue.jobs().len(), 3);
+        queue.clear_finished();
+        let left: Vec<JobId> = queue.jobs().iter().map(|job| job.id).collect();
+        assert_eq!(left, [ids[1], ids[2]]);
+    }
+
+    #[test]
+    fn skips_jobs_already_running() {
+        let (mut queue, ids) = queue_of(3);
+        queue.get_mut(ids[0]).unwrap().status = JobStatus::Downloading;
+        queue.get_mut(ids[1]).unwrap().status = JobStatus::Paused;
+        assert_eq!(queue.next_queued(Instant::now()), Some(ids[2]));
+
+        queue.get_mut(ids[2]).unwrap().status = JobStatus::Downloading;
+        assert_eq!(queue.next_queued(Instant::now()), None);
+    }
+
+    #[test]
+    fn waits_for_the_retry_delay() {
+        let (mut queue, ids) = queue_of(2);
+        let now = Instant::now();
+        queue.get_mut(ids[0]).unwrap().retry_at = Some(now + Duration::from_secs(5));
+
+        assert!(queue.has_pending_retry());
+        assert_eq!(queue.next_queued(now), Some(ids[1]));
+        assert_eq!(queue.next_queued(now + Duration::from_secs(5)), Some(ids[0]));
+    }
+
+    #[test]
+    fn retrying_by_hand_starts_the_count_over() {
+        let (mut queue, ids) = queue_of(1);
+        let job = queue.get_mut(ids[0]).unwrap();
+        job.status = JobStatus::Failed("HTTP Error 503".to_string());
+        job.attempts = 3;
+        job.updated_for_retry = true;
+
+        queue.retry(ids[0]);
+        let job = queue.get(ids[0]).unwrap();
+        assert_eq!(job.status, JobStatus::Queued);
+        assert_eq!(job.attempts, 0);
+        assert!(!job.updated_for_retry);
+        assert!(!queue.has_pending_retry());
+    }
+}
@@ -0,0 +1,291 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::history::{HistoryEntry, Outcome};
+use crate::models::DownloadFormat;
+use crate::storage::unix_now;
+
+// Exported download records. The layout is described in the README under
+// "Download records"; bump the version on any incompatible change
+const RECORDS_KIND: &str = "ytdl-gui-records";
+pub const RECORDS_VERSION: u32 = 1;
+
+// One download as written to JSON and CSV exports. Kept separate from
+// HistoryEntry so the history file can change without breaking the format
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub url: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub extractor: Option<String>,
+    #[serde(default)]
+    pub media_id: Option<String>,
+    // "mp4" or "mp3"
+    pub format: String,
+    #[serde(default)]
+    pub format_spec: Option<String>,
+    pub download_dir: String,
+    #[serde(default)]
+    pub output_path: Option<PathBuf>,
+    #[serde(default)]
+    pub size_bytes: Option<u64>,
+    #[serde(default)]
+    pub duration_seconds: Option<f64>,
+    // "completed", "failed" or "cancelled"
+    pub outcome: String,
+    #[serde(default)]
+    pub error: Option<String>,
+    // Unix times in seconds
+    pub queued_at: u64,
+    #[serde(default)]
+    pub started_at: Option<u64>,
+    pub finished_at: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordsFile {
+    kind: String,
+    version: u32,
+    exported_at: u64,
+    records: Vec<Record>,
+}
+
+fn format_name(format: DownloadFormat) -> &'static str {
+    match format {
+        DownloadFormat::MP4 => "mp4",
+        DownloadFormat::MP3 => "mp3",
+    }
+}
+
+fn outcome_name(outcome: Outcome) -> &'static str {
+    match outcome {
+        Outcome::Completed => "completed",
+        Outcome::Failed => "failed",
+        Outcome::Cancelled => "cancelled",
+    }
+}
+
+impl From<&HistoryEntry> for Record {
+    fn from(entry: &HistoryEntry) -> Self {
+        Self {
+            url: entry.url.clone(),
+            title: entry.title.clone(),
+            extractor: entry.extractor.clone(),
+            media_id: entry.media_id.clone(),
+            format: format_name(entry.format).to_string(),
+            format_spec: entry.format_spec.clone(),
+            download_dir: entry.download_dir.clone(),
+            output_path: entry.output_path.clone(),
+            size_bytes: entry.size,
+            duration_seconds: entry.duration,
+            outcome: outcome_name(entry.outcome).to_string(),
+            error: entry.error.clone(),
+            queued_at: entry.queued_at,
+            started_at: entry.started_at,
+            finished_at: entry.finished_at,
+        }
+    }
+}
+
+impl Record {
+    // History entry for an imported record; the id is assigned by History
+    pub fn to_history_entry(&self) -> Result<HistoryEntry, String> {
+        let format = match self.format.as_str() {
+            "mp4" => DownloadFormat::MP4,
+            "mp3" => DownloadFormat::MP3,
+            other => return Err(format!("Unknown format \"{}\" for {}", other, self.url)),
+        };
+        let outcome = match self.outcome.as_str() {
+            "completed" => Outcome::Completed,
+            "failed" => Outcome::Failed,
+            "cancelled" => Outcome::Cancelled,
+            other => return Err(format!("Unknown outcome \"{}\" for {}", other, self.url)),
+        };
+        Ok(HistoryEntry {
+            id: 0,
+            url: self.url.clone(),
+            title: self.title.clone(),
+            extractor: self.extractor.clone(),
+            media_id: self.media_id.clone(),
+            format,
+            format_spec: self.format_spec.clone(),
+            download_dir: self.download_dir.clone(),
+            output_path: self.output_path.clone(),
+            output_paths: self.output_path.iter().cloned().collect(),
+            size: self.size_bytes,
+            duration: self.duration_seconds,
+            queued_at: self.queued_at,
+            started_at: self.started_at,
+            finished_at: self.finished_at,
+            outcome,
+            error: self.error.clone(),
+        })
+    }
+}
+
+pub fn export_json(path: &Path, entries: &[HistoryEntry]) -> Result<(), String> {
+    let file = RecordsFile {
+        kind: RECORDS_KIND.to_string(),
+        version: RECORDS_VERSION,
+        exported_at: unix_now(),
+        records: entries.iter().map(Record::from).collect(),
+    };
+    let content = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+    fs::write(path, content).map_err(|e| e.to_string())
+}
+
+// Quotes a CSV field when it holds a separator, quote or line break (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+const CSV_HEADER: [&str; 15] = [
+    "url",
+    "title",
+    "extractor",
+    "media_id",
+    "format",
+    "format_spec",
+    "download_dir",
+    "output_path",
+    "size_bytes",
+    "duration_seconds",
+    "outcome",
+    "error",
+    "queued_at",
+    "started_at",
+    "finished_at",
+];
+
+pub fn export_csv(path: &Path, entries: &[HistoryEntry]) -> Result<(), String> {
+    let mut content = CSV_HEADER.join(",");
+    content.push_str("\r\n");
+
+    for record in entries.iter().map(Record::from) {
+        let optional = |value: Option<String>| value.unwrap_or_default();
+        let fields = [
+            record.url,
+            optional(record.title),
+            optional(record.extractor),
+            optional(record.media_id),
+            record.format,
+            optional(record.format_spec),
+            record.download_dir,
+            optional(record.output_path.map(|p| p.to_string_lossy().to_string())),
+            optional(record.size_bytes.map(|size| size.to_string())),
+            optional(record.duration_seconds.map(|duration| duration.to_string())),
+            record.outcome,
+            optional(record.error),
+            record.queued_at.to_string(),
+            optional(record.started_at.map(|at| at.to_string())),
+            record.finished_at.to_string(),
+        ];
+        let line: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        content.push_str(&line.join(","));
+        content.push_str("\r\n");
+    }
+
+    fs::write(path, content).map_err(|e| e.to_string())
+}
+
+// Reads a JSON export, refusing files from a newer, unknown version
+pub fn import_json(path: &Path) -> Result<Vec<Record>, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    parse_json(&content)
+}
+
+fn parse_json(content: &str) -> Result<Vec<Record>, String> {
+    let file: RecordsFile = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    if file.kind != RECORDS_KIND {
+        return Err(format!("Not a download records file: \"{}\"", file.kind));
+    }
+    if file.version > RECORDS_VERSION {
+        return Err(format!(
+            "Records version {} is newer than the supported version {}",
+            file.version, RECORDS_VERSION
+        ));
+    }
+    Ok(file.records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> HistoryEntry {
+        HistoryEntry {
+            id: 0,
+            url: "https://example.com/watch?v=abc".to_string(),
+            title: Some("A \"quoted\", title".to_string()),
+            extractor: Some("Youtube".to_string()),
+            media_id: Some("abc".to_string()),
+            format: DownloadFormat::MP3,
+            format_spec: Some("bestaudio".to_string()),
+            download_dir: "/home/user/Music".to_string(),
+            output_path: Some(PathBuf::from("/home/user/Music/A title.mp3")),
+            output_paths: vec![PathBuf::from("/home/user/Music/A title.mp3")],
+            size: Some(1234),
+            duration: Some(61.5),
+            queued_at: 100,
+            started_at: Some(110),
+            finished_at: 200,
+            outcome: Outcome::Failed,
+            error: Some("HTTP Error 403".to_string()),
+        }
+    }
+
+    #[test]
+    fn csv_field_quotes_only_when_needed() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn record_round_trips_to_a_history_entry() {
+        let entry = entry();
+        let record = Record::from(&entry);
+        assert_eq!(record.format, "mp3");
+        assert_eq!(record.outcome, "failed");
+        assert_eq!(record.to_history_entry(), Ok(entry));
+    }
+
+    #[test]
+    fn unknown_format_or_outcome_is_refused() {
+        let mut record = Record::from(&entry());
+        record.format = "webm".to_string();
+        assert!(record.to_history_entry().is_err());
+
+        let mut record = Record::from(&entry());
+        record.outcome = "skipped".to_string();
+        assert!(record.to_history_entry().is_err());
+    }
+
+    fn records_file(kind: &str, version: u32) -> String {
+        serde_json::to_string(&RecordsFile {
+            kind: kind.to_string(),
+            version,
+            exported_at: 0,
+            records: vec![Record::from(&entry())],
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_json_checks_kind_and_version() {
+        assert_eq!(
+            parse_json(&records_file(RECORDS_KIND, RECORDS_VERSION)),
+            Ok(vec![Record::from(&entry())])
+        );
+        assert!(parse_json(&records_file("something-else", RECORDS_VERSION)).is_err());
+        assert!(parse_json(&records_file(RECORDS_KIND, RECORDS_VERSION + 1)).is_err());
+        assert!(parse_json("not json").is_err());
+    }
+}
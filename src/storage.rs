@@ -0,0 +1,44 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Directory holding the managed yt-dlp binary and the app's own state files
+pub fn config_dir() -> PathBuf {
+    let mut path = dirs::config_dir().unwrap_or_else(|| "./".into());
+    path.push("ytdl-gui");
+    if !path.exists() {
+        let _ = fs::create_dir_all(&path);
+    }
+    path
+}
+
+pub fn load_json<T: DeserializeOwned>(file_name: &str) -> Option<T> {
+    let content = fs::read_to_string(config_dir().join(file_name)).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            log::warn!("Ignoring unreadable {}: {}", file_name, e);
+            None
+        }
+    }
+}
+
+// Writes to a temporary file first so a crash mid-write never leaves a
+// truncated state file behind
+pub fn save_json<T: Serialize>(file_name: &str, value: &T) -> Result<(), String> {
+    let path = config_dir().join(file_name);
+    let tmp_path = path.with_extension("json.tmp");
+    let content = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    fs::write(&tmp_path, content).map_err(|e| e.to_string())?;
+    fs::rename(&tmp_path, &path).map_err(|e| e.to_string())
+}
+
+// Seconds since the Unix epoch, the unit timestamps in state files use
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
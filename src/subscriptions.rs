@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{DownloadFormat, PlaylistEntry};
+
+pub type SubscriptionId = u64;
+
+pub const MIN_CHECK_INTERVAL_MINUTES: u64 = 15;
+pub const MAX_CHECK_INTERVAL_MINUTES: u64 = 24 * 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubscriptionFilters {
+    // Case-insensitive text the title must contain; empty matches everything
+    pub title_contains: String,
+    // Duration bounds in minutes, 0 meaning no bound. Entries whose duration
+    // is unknown are never filtered out by them
+    pub min_duration_minutes: u32,
+    pub max_duration_minutes: u32,
+    // How many of the newest entries each check looks at
+    pub max_items: u32,
+}
+
+impl Default for SubscriptionFilters {
+    fn default() -> Self {
+        Self {
+            title_contains: String::new(),
+            min_duration_minutes: 0,
+            max_duration_minutes: 0,
+            max_items: 20,
+        }
+    }
+}
+
+impl SubscriptionFilters {
+    pub fn matches(&self, entry: &PlaylistEntry) -> bool {
+        let needle = self.title_contains.trim().to_lowercase();
+        if !needle.is_empty() {
+            let title = entry.title.as_deref().unwrap_or_default().to_lowercase();
+            if !title.contains(&needle) {
+                return false;
+            }
+        }
+
+        if let Some(duration) = entry.duration {
+            let minutes = duration / 60.0;
+            if self.min_duration_minutes > 0 && minutes < self.min_duration_minutes as f64 {
+                return false;
+            }
+            if self.max_duration_minutes > 0 && minutes > self.max_duration_minutes as f64 {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+// A channel or playlist checked on a schedule for entries not downloaded yet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Subscription {
+    pub id: SubscriptionId,
+    pub url: String,
+    pub name: String,
+    pub format: DownloadFormat,
+    pub download_dir: String,
+    #[serde(default)]
+    pub filters: SubscriptionFilters,
+    // Record what the first check finds as already downloaded instead of
+    // downloading the whole back catalogue
+    #[serde(default)]
+    pub skip_existing: bool,
+    // Unix time of the last successful check
+    #[serde(default)]
+    pub last_checked: Option<u64>,
+    // Unix time the last check was started, so failing checks wait for the
+    // next interval too
+    #[serde(skip)]
+    pub last_attempt: Option<u64>,
+    // Entries queued by the last check
+    #[serde(skip)]
+    pub new_count: usize,
+    #[serde(skip)]
+    pub checking: bool,
+    // "Check now" was pressed while yt-dlp was being updated
+    #[serde(skip)]
+    pub check_requested: bool,
+    #[serde(skip)]
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct SubscriptionList {
+    subscriptions: Vec<Subscription>,
+    next_id: SubscriptionId,
+}
+
+impl SubscriptionList {
+    pub fn from_saved(subscriptions: Vec<Subscription>) -> Self {
+        let next_id = subscriptions.iter().map(|s| s.id).max().unwrap_or(0);
+        Self {
+            subscriptions,
+            next_id,
+        }
+    }
+
+    pub fn add(
+        &mut self,
+        url: String,
+        name: String,
+        format: DownloadFormat,
+        download_dir: String,
+        filters: SubscriptionFilters,
+        skip_existing: bool,
+    ) -> SubscriptionId {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.subscriptions.push(Subscription {
+            id,
+            url,
+            name,
+            format,
+            download_dir,
+            filters,
+            skip_existing,
+            last_checked: None,
+            last_attempt: None,
+            new_count: 0,
+            checking: false,
+            check_requested: false,
+            error: None,
+        });
+        id
+    }
+
+    pub fn remove(&mut self, id: SubscriptionId) {
+        self.subscriptions.retain(|subscription| subscription.id != id);
+    }
+
+    pub fn subscriptions(&self) -> &[Subscription] {
+        &self.subscriptions
+    }
+
+    pub fn get_mut(&mut self, id: SubscriptionId) -> Option<&mut Subscription> {
+        self.subscriptions.iter_mut().find(|s| s.id == id)
+    }
+
+    pub fn is_checking(&self) -> bool {
+        self.subscriptions.iter().any(|s| s.checking)
+    }
+
+    // Subscription whose last check is the oldest among those older than the
+    // interval; ones checked by hand while that was not possible come first,
+    // then never-checked ones
+    pub fn next_due(&self, now: u64, interval_secs: u64) -> Option<SubscriptionId> {
+        self.subscriptions
+            .iter()
+            .filter(|s| !s.checking)
+            .map(|s| (s.id, s.check_requested, s.last_checked.max(s.last_attempt)))
+            .filter(|(_, requested, last)| {
+                *requested || last.map_or(true, |last| now.saturating_sub(last) >= interval_secs)
+            })
+            .min_by_key(|(_, requested, last)| (!requested, *last))
+            .map(|(id, _, _)| id)
+    }
+}
+
+// Fields of the "Add subscription" form
+pub struct SubscriptionForm {
+    pub url: String,
+    pub name: String,
+    pub format: DownloadFormat,
+    pub download_dir: String,
+    pub filters: SubscriptionFilters,
+    pub skip_existing: bool,
+}
+
+impl Default for SubscriptionForm {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            name: String::new(),
+            format: DownloadFormat::default(),
+            download_dir: String::new(),
+            filters: SubscriptionFilters::default(),
+            skip_existing: true,
+        }
+    }
+}
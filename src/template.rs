@@ -0,0 +1,599 @@
+use serde_json::Value;
+use std::path::Path;
+
+use crate::models::{DownloadFormat, VideoMetadata};
+
+pub const DEFAULT_OUTPUT_TEMPLATE: &str = "%(title)s.%(ext)s";
+
+// Fields offered by the template editor's insert buttons
+pub const TEMPLATE_FIELDS: [&str; 12] = [
+    "title",
+    "uploader",
+    "channel",
+    "upload_date",
+    "id",
+    "playlist",
+    "playlist_index",
+    "resolution",
+    "height",
+    "fps",
+    "duration_string",
+    "ext",
+];
+
+// Conversion types yt-dlp accepts after `%(field)`
+const CONVERSIONS: &str = "diouxXeEfFgGcrsaBjhlqDSU";
+// Flags, width and precision allowed between `)` and the conversion
+const FORMAT_FLAGS: &str = "0123456789.-+ #";
+
+// Arithmetic yt-dlp allows on numeric fields, as in `%(playlist_index+10)d`
+const MATH_OPERATORS: [char; 3] = ['+', '-', '*'];
+
+// One field inside a placeholder: `name.key.key`, then arithmetic, then a
+// `>` date format
+#[derive(Debug, Clone, PartialEq)]
+struct FieldExpr {
+    name: String,
+    // Object keys or list indices traversed from the field
+    keys: Vec<String>,
+    // Operator and operand, a number or another field path
+    maths: Vec<(char, String)>,
+    strftime: Option<String>,
+}
+
+// One `%(...)X` placeholder
+#[derive(Debug, Clone, PartialEq)]
+struct Placeholder {
+    // Alternatives separated by commas, first present one wins
+    fields: Vec<FieldExpr>,
+    // Text after `&`, used instead of the value when there is one
+    replacement: Option<String>,
+    default: Option<String>,
+    flags: String,
+    conversion: char,
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+// Parses one alternative. A `-` right after a `.` is a negative list index
+// rather than a subtraction
+fn parse_field(spec: &str) -> Result<FieldExpr, String> {
+    let (spec, strftime) = match spec.split_once('>') {
+        Some((spec, strftime)) => (spec, Some(strftime.to_string())),
+        None => (spec, None),
+    };
+
+    let mut paths = vec![(None, String::new())];
+    for c in spec.trim().chars() {
+        let path = &mut paths.last_mut().expect("never empty").1;
+        if MATH_OPERATORS.contains(&c) && !(c == '-' && (path.is_empty() || path.ends_with('.'))) {
+            paths.push((Some(c), String::new()));
+        } else {
+            path.push(c);
+        }
+    }
+
+    let mut paths = paths.into_iter();
+    let (_, path) = paths.next().expect("never empty");
+    let mut segments = path.split('.');
+    let name = segments.next().unwrap_or_default().to_string();
+    if name.is_empty() || !name.chars().all(is_name_char) {
+        return Err(format!("\"{}\" is not a field name", path));
+    }
+    let keys: Vec<String> = segments.map(str::to_string).collect();
+    if keys.iter().any(String::is_empty) {
+        return Err(format!("Empty key in \"{}\"", path));
+    }
+
+    let mut maths = Vec::new();
+    for (operator, operand) in paths {
+        if operand.is_empty() {
+            return Err(format!("\"{}\" is missing a number or field", spec.trim()));
+        }
+        maths.push((operator.expect("only the first path has no operator"), operand));
+    }
+
+    Ok(FieldExpr {
+        name,
+        keys,
+        maths,
+        strftime,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Text(String),
+    Field(Placeholder),
+}
+
+fn parse(template: &str) -> Result<Vec<Part>, String> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            text.push(c);
+            continue;
+        }
+        match chars.peek() {
+            Some('%') => {
+                chars.next();
+                text.push('%');
+                continue;
+            }
+            Some('(') => {
+                chars.next();
+            }
+            _ => return Err("\"%\" must start a field like %(title)s; write %% for a literal %".to_string()),
+        }
+
+        let mut inner = String::new();
+        let mut depth = 1;
+        loop {
+            match chars.next() {
+                Some('(') => {
+                    depth += 1;
+                    inner.push('(');
+                }
+                Some(')') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                    inner.push(')');
+                }
+                Some(c) => inner.push(c),
+                None => return Err(format!("Unclosed field \"%({}\"", inner)),
+            }
+        }
+
+        let mut flags = String::new();
+        while let Some(&c) = chars.peek() {
+            if !FORMAT_FLAGS.contains(c) {
+                break;
+            }
+            flags.push(c);
+            chars.next();
+        }
+        let conversion = match chars.next() {
+            Some(c) if CONVERSIONS.contains(c) => c,
+            _ => {
+                return Err(format!(
+                    "Field \"%({})\" needs a type after it, e.g. %({})s",
+                    inner, inner
+                ))
+            }
+        };
+
+        // `fields&replacement|default`, as yt-dlp splits them
+        let (rest, default) = match inner.split_once('|') {
+            Some((rest, default)) => (rest, Some(default.to_string())),
+            None => (inner.as_str(), None),
+        };
+        let (names, replacement) = match rest.split_once('&') {
+            Some((names, replacement)) => (names, Some(replacement.to_string())),
+            None => (rest, None),
+        };
+        if names.split(',').any(|name| name.trim().is_empty()) {
+            return Err("Empty field name in \"%()\"".to_string());
+        }
+        let fields = names.split(',').map(parse_field).collect::<Result<Vec<_>, _>>()?;
+
+        if !text.is_empty() {
+            parts.push(Part::Text(std::mem::take(&mut text)));
+        }
+        parts.push(Part::Field(Placeholder {
+            fields,
+            replacement,
+            default,
+            flags,
+            conversion,
+        }));
+    }
+
+    if !text.is_empty() {
+        parts.push(Part::Text(text));
+    }
+    Ok(parts)
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Validation {
+    // The template can't be used at all
+    pub error: Option<String>,
+    // Usable, but probably not what was meant
+    pub warnings: Vec<String>,
+}
+
+pub fn validate(template: &str) -> Validation {
+    let mut validation = Validation::default();
+    if template.trim().is_empty() {
+        validation.error = Some("The template is empty".to_string());
+        return validation;
+    }
+    let parts = match parse(template) {
+        Ok(parts) => parts,
+        Err(e) => {
+            validation.error = Some(e);
+            return validation;
+        }
+    };
+
+    if template.starts_with('/') {
+        validation.error =
+            Some("Use a path relative to the download directory".to_string());
+        return validation;
+    }
+    if template.split('/').any(|segment| segment == "..") {
+        validation.error = Some("\"..\" would leave the download directory".to_string());
+        return validation;
+    }
+
+    let has_field = |name: &str| {
+        parts.iter().any(|part| matches!(part, Part::Field(p) if p.fields.iter().any(|f| f.name == name)))
+    };
+    if !template.trim_end().ends_with("%(ext)s") {
+        validation
+            .warnings
+            .push("Without %(ext)s at the end files may get no extension".to_string());
+    }
+    if !has_field("id") && !has_field("title") {
+        validation
+            .warnings
+            .push("Without %(title)s or %(id)s different videos get the same name".to_string());
+    }
+    validation
+}
+
+// Value of a metadata field as yt-dlp would substitute it. Only the final
+// extension differs, since it depends on the chosen format
+fn field_value(metadata: &VideoMetadata, name: &str, format: DownloadFormat) -> Option<Value> {
+    match name {
+        "ext" => Some(Value::from(match format {
+            DownloadFormat::MP4 => "mp4",
+            DownloadFormat::MP3 => "mp3",
+        })),
+        "id" => Some(Value::from(metadata.id.clone())),
+        "title" => Some(Value::from(metadata.title.clone())),
+        "uploader" => metadata.uploader.clone().map(Value::from),
+        "channel" => metadata.channel.clone().map(Value::from),
+        "upload_date" => metadata.upload_date.clone().map(Value::from),
+        "duration" => metadata.duration.map(Value::from),
+        "view_count" => metadata.view_count.map(Value::from),
+        _ => metadata.extra.get(name).filter(|value| !value.is_null()).cloned(),
+    }
+}
+
+// Follows object keys and list indices into a field; negative indices count
+// from the end. Slices are left to yt-dlp
+fn lookup(metadata: &VideoMetadata, name: &str, keys: &[String], format: DownloadFormat) -> Result<Option<Value>, String> {
+    let Some(mut value) = field_value(metadata, name, format) else {
+        return Ok(None);
+    };
+    for key in keys {
+        if key.contains(':') {
+            return Err("slices are not previewed".to_string());
+        }
+        let next = match &value {
+            Value::Object(map) => map.get(key).cloned(),
+            Value::Array(items) => key.parse::<i64>().ok().and_then(|index| {
+                let index = if index < 0 { items.len() as i64 + index } else { index };
+                usize::try_from(index).ok().and_then(|index| items.get(index).cloned())
+            }),
+            _ => None,
+        };
+        match next.filter(|value| !value.is_null()) {
+            Some(next) => value = next,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(value))
+}
+
+fn field_expr_value(metadata: &VideoMetadata, expr: &FieldExpr, format: DownloadFormat) -> Result<Option<Value>, String> {
+    if expr.strftime.is_some() {
+        return Err("date formats after \">\" are not previewed".to_string());
+    }
+    let Some(value) = lookup(metadata, &expr.name, &expr.keys, format)? else {
+        return Ok(None);
+    };
+    if expr.maths.is_empty() {
+        return Ok(Some(value));
+    }
+
+    let Some(mut number) = value.as_f64() else {
+        return Ok(None);
+    };
+    for (operator, operand) in &expr.maths {
+        let operand = match operand.parse::<f64>() {
+            Ok(operand) => operand,
+            Err(_) => {
+                let mut segments = operand.split('.');
+                let name = segments.next().unwrap_or_default();
+                let keys: Vec<String> = segments.map(str::to_string).collect();
+                match lookup(metadata, name, &keys, format)?.and_then(|value| value.as_f64()) {
+                    Some(operand) => operand,
+                    None => return Ok(None),
+                }
+            }
+        };
+        number = match operator {
+            '+' => number + operand,
+            '-' => number - operand,
+            _ => number * operand,
+        };
+    }
+    Ok(Some(if number.fract() == 0.0 {
+        Value::from(number as i64)
+    } else {
+        Value::from(number)
+    }))
+}
+
+fn format_value(value: &Value, placeholder: &Placeholder) -> String {
+    let width: usize = placeholder
+        .flags
+        .trim_start_matches(['0', '-', '+', ' ', '#'])
+        .split('.')
+        .next()
+        .and_then(|width| width.parse().ok())
+        .unwrap_or(0);
+    let zero_pad = placeholder.flags.starts_with('0');
+
+    match (placeholder.conversion, value) {
+        ('d' | 'i', Value::Number(number)) => {
+            let number = number.as_f64().unwrap_or_default() as i64;
+            if zero_pad {
+                format!("{:0width$}", number, width = width)
+            } else {
+                format!("{:width$}", number, width = width)
+            }
+        }
+        ('j', value) => value.to_string(),
+        (_, Value::String(text)) => text.clone(),
+        (_, value) => value.to_string(),
+    }
+}
+
+// Resolves the template the way yt-dlp would name the file, for the preview.
+// Missing fields become "NA" like in yt-dlp. Errors for templates yt-dlp
+// accepts but the preview can't resolve, such as date formats
+pub fn preview(template: &str, metadata: &VideoMetadata, format: DownloadFormat) -> Result<String, String> {
+    let mut output = String::new();
+    for part in parse(template)? {
+        match part {
+            Part::Text(text) => output.push_str(&text),
+            Part::Field(placeholder) => {
+                let mut value = None;
+                for expr in &placeholder.fields {
+                    value = field_expr_value(metadata, expr, format)?;
+                    if value.is_some() {
+                        break;
+                    }
+                }
+                let text = match (value, &placeholder.replacement) {
+                    (Some(value), Some(replacement)) => {
+                        // Python's str.format; only a bare {} is previewed
+                        if replacement.replace("{}", "").contains(['{', '}']) {
+                            return Err("format specs in \"&\" replacements are not previewed".to_string());
+                        }
+                        replacement.replace("{}", &format_value(&value, &placeholder))
+                    }
+                    (Some(value), None) => format_value(&value, &placeholder),
+                    (None, _) => placeholder.default.clone().unwrap_or_else(|| "NA".to_string()),
+                };
+                // yt-dlp never lets a field value add directories
+                output.push_str(&text.replace('/', "⧸"));
+            }
+        }
+    }
+    Ok(output)
+}
+
+// Literal `-o` for a second file next to `existing`: the video id is
+// appended first, then a counter, until the name is free. The extension
+// is left to yt-dlp since merging or converting may change it
+pub fn renamed_template(existing: &Path, id: Option<&str>) -> String {
+    let dir = existing.parent().unwrap_or(Path::new(""));
+    let stem = existing
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = existing
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut candidates = Vec::new();
+    if let Some(id) = id.filter(|id| !stem.contains(id)) {
+        candidates.push(format!("{} [{}]", stem, id));
+    }
+    let name = candidates
+        .into_iter()
+        .chain((1..).map(|n| format!("{} ({})", stem, n)))
+        .find(|name| !dir.join(format!("{}.{}", name, ext)).exists())
+        .unwrap_or(stem);
+
+    let path = dir.join(name).to_string_lossy().replace('%', "%%");
+    format!("{}.%(ext)s", path)
+}
+
+// `-o chapter:` template for split chapters: a folder named like the video
+// file would be, holding the chapters numbered in order
+pub fn chapter_template(template: &str) -> String {
+    let stem = template.strip_suffix(".%(ext)s").unwrap_or(template);
+    format!("chapter:{}/%(section_number)02d - %(section_title)s.%(ext)s", stem)
+}
+
+// Template for renaming the items of a playlist, which can't each be given
+// a literal name: the video id goes in front of the extension
+pub fn with_id_suffix(template: &str) -> String {
+    match template.strip_suffix(".%(ext)s") {
+        Some(stem) => format!("{} [%(id)s].%(ext)s", stem),
+        None => format!("{} [%(id)s]", template),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn metadata() -> VideoMetadata {
+        serde_json::from_str(
+            r#"{
+                "id": "abc123",
+                "title": "Intro/Outro",
+                "uploader": "Someone",
+                "upload_date": "20240131",
+                "playlist_index": 7,
+                "playlist_count": 12,
+                "tags": ["music", "live"],
+                "channel_follower_count": null
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn preview_mp4(template: &str) -> Result<String, String> {
+        preview(template, &metadata(), DownloadFormat::MP4)
+    }
+
+    #[test]
+    fn parses_text_fields_and_escapes() {
+        let parts = parse("100%% %(title,id|none)s.%(ext)s").unwrap();
+        assert_eq!(parts.len(), 4);
+        assert_eq!(parts[0], Part::Text("100% ".to_string()));
+        match &parts[1] {
+            Part::Field(placeholder) => {
+                let names: Vec<&str> = placeholder.fields.iter().map(|f| f.name.as_str()).collect();
+                assert_eq!(names, ["title", "id"]);
+                assert_eq!(placeholder.default.as_deref(), Some("none"));
+                assert_eq!(placeholder.conversion, 's');
+            }
+            part => panic!("unexpected part {:?}", part),
+        }
+    }
+
+    #[test]
+    fn parses_operators_apart_from_the_name() {
+        let parts = parse("%(tags.-1)s %(playlist_index+10)03d %(upload_date>%Y-%m-%d)s %(uploader&by {})s").unwrap();
+        let fields: Vec<&Placeholder> = parts
+            .iter()
+            .filter_map(|part| match part {
+                Part::Field(placeholder) => Some(placeholder),
+                Part::Text(_) => None,
+            })
+            .collect();
+        assert_eq!(fields[0].fields[0].name, "tags");
+        assert_eq!(fields[0].fields[0].keys, ["-1"]);
+        assert_eq!(fields[1].fields[0].name, "playlist_index");
+        assert_eq!(fields[1].fields[0].maths, [('+', "10".to_string())]);
+        assert_eq!(fields[2].fields[0].strftime.as_deref(), Some("%Y-%m-%d"));
+        assert_eq!(fields[3].fields[0].name, "uploader");
+        assert_eq!(fields[3].replacement.as_deref(), Some("by {}"));
+    }
+
+    #[test]
+    fn rejects_malformed_templates() {
+        assert!(parse("50% off").is_err());
+        assert!(parse("%(title").is_err());
+        assert!(parse("%(title)").is_err());
+        assert!(parse("%()s").is_err());
+        assert!(parse("%(playlist_index+)s").is_err());
+    }
+
+    #[test]
+    fn validates_paths_and_warns_about_missing_fields() {
+        assert!(validate(DEFAULT_OUTPUT_TEMPLATE).error.is_none());
+        assert!(validate(DEFAULT_OUTPUT_TEMPLATE).warnings.is_empty());
+        assert!(validate("").error.is_some());
+        assert!(validate("/tmp/%(title)s.%(ext)s").error.is_some());
+        assert!(validate("../%(title)s.%(ext)s").error.is_some());
+        assert_eq!(validate("%(uploader)s").warnings.len(), 2);
+    }
+
+    #[test]
+    fn previews_like_ytdlp() {
+        assert_eq!(preview_mp4(DEFAULT_OUTPUT_TEMPLATE).unwrap(), "Intro⧸Outro.mp4");
+        assert_eq!(
+            preview(DEFAULT_OUTPUT_TEMPLATE, &metadata(), DownloadFormat::MP3).unwrap(),
+            "Intro⧸Outro.mp3"
+        );
+        assert_eq!(preview_mp4("%(playlist_index)03d").unwrap(), "007");
+        assert_eq!(preview_mp4("%(channel,uploader)s").unwrap(), "Someone");
+        assert_eq!(preview_mp4("%(channel)s").unwrap(), "NA");
+        assert_eq!(preview_mp4("%(channel_follower_count|0)s").unwrap(), "0");
+    }
+
+    #[test]
+    fn previews_traversal_arithmetic_and_replacement() {
+        assert_eq!(preview_mp4("%(tags.0)s-%(tags.-1)s").unwrap(), "music-live");
+        assert_eq!(preview_mp4("%(tags.5|none)s").unwrap(), "none");
+        assert_eq!(preview_mp4("%(playlist_index+10)d").unwrap(), "17");
+        assert_eq!(preview_mp4("%(playlist_count-playlist_index)d").unwrap(), "5");
+        assert_eq!(preview_mp4("%(uploader&by {})s").unwrap(), "by Someone");
+        assert_eq!(preview_mp4("%(channel&by {}|anonymous)s").unwrap(), "anonymous");
+    }
+
+    #[test]
+    fn refuses_to_guess_what_it_cannot_resolve() {
+        assert!(preview_mp4("%(upload_date>%Y-%m-%d)s").is_err());
+        assert!(preview_mp4("%(title.:5)s").is_err());
+        assert!(preview_mp4("%(title&{:>20})s").is_err());
+    }
+
+    #[test]
+    fn renames_with_the_id_then_a_counter() {
+        let dir = std::env::temp_dir().join(format!("template-rename-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let existing = dir.join("Song.mp3");
+        fs::write(&existing, b"").unwrap();
+        let expected = |name: &str| format!("{}.%(ext)s", dir.join(name).to_string_lossy());
+
+        assert_eq!(renamed_template(&existing, Some("abc123")), expected("Song [abc123]"));
+        assert_eq!(renamed_template(&existing, None), expected("Song (1)"));
+
+        fs::write(dir.join("Song [abc123].mp3"), b"").unwrap();
+        fs::write(dir.join("Song (1).mp3"), b"").unwrap();
+        assert_eq!(renamed_template(&existing, Some("abc123")), expected("Song (2)"));
+
+        // An id already in the name isn't added twice
+        let tagged = dir.join("Song [abc123].mp3");
+        assert_eq!(renamed_template(&tagged, Some("abc123")), expected("Song [abc123] (1)"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn renamed_template_escapes_percent_signs() {
+        let existing = Path::new("/nonexistent/100% Song.mp4");
+        assert_eq!(renamed_template(existing, None), "/nonexistent/100%% Song (1).%(ext)s");
+    }
+
+    #[test]
+    fn chapters_go_next_to_the_video() {
+        assert_eq!(
+            chapter_template(DEFAULT_OUTPUT_TEMPLATE),
+            "chapter:%(title)s/%(section_number)02d - %(section_title)s.%(ext)s"
+        );
+        assert_eq!(
+            chapter_template("%(uploader)s/%(title)s [%(id)s].%(ext)s"),
+            "chapter:%(uploader)s/%(title)s [%(id)s]/%(section_number)02d - %(section_title)s.%(ext)s"
+        );
+    }
+
+    #[test]
+    fn adds_the_id_before_the_extension() {
+        assert_eq!(with_id_suffix(DEFAULT_OUTPUT_TEMPLATE), "%(title)s [%(id)s].%(ext)s");
+        assert_eq!(
+            with_id_suffix("%(uploader)s/%(title)s.%(ext)s"),
+            "%(uploader)s/%(title)s [%(id)s].%(ext)s"
+        );
+        assert_eq!(with_id_suffix("%(title)s"), "%(title)s [%(id)s]");
+    }
+}
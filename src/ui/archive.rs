@@ -0,0 +1,112 @@
+use eframe::egui;
+
+use crate::localizations::Localizations;
+use crate::models::AppState;
+
+use crate::theme::*;
+
+pub enum ArchiveAction {
+    Remove(String),
+    Import,
+    Export,
+    Reload,
+}
+
+// Archive tab: searchable list of everything yt-dlp recorded as downloaded
+pub fn render_archive(
+    ui: &mut egui::Ui,
+    state: &mut AppState,
+    localizer: &Localizations,
+) -> Option<ArchiveAction> {
+    let mut action = None;
+    let view = &mut state.archive;
+    let text = |key: &str, fallback: &str| {
+        localizer
+            .lookup_single_language(key, None)
+            .unwrap_or_else(|| fallback.to_string())
+    };
+
+    ui.label(
+        egui::RichText::new(text(
+            "archive-description",
+            "Videos listed here are skipped by every download. Remove one to download it again.",
+        ))
+        .color(SECONDARY_TEXT)
+        .size(13.0),
+    );
+    ui.add_space(6.0);
+
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(&mut view.search)
+                .hint_text(text("archive-search", "Search by site or video ID")),
+        );
+        if ui.button(text("import-button", "Import...")).clicked() {
+            action = Some(ArchiveAction::Import);
+        }
+        if ui.button(text("export-button", "Export...")).clicked() {
+            action = Some(ArchiveAction::Export);
+        }
+        if ui.button(text("reload-button", "Reload")).clicked() {
+            action = Some(ArchiveAction::Reload);
+        }
+    });
+
+    if let Some(message) = &view.message {
+        ui.label(egui::RichText::new(message).color(SECONDARY_TEXT).size(13.0));
+    }
+    if view.locked {
+        ui.label(
+            egui::RichText::new(text(
+                "archive-locked",
+                "Entries can be removed once running downloads finish.",
+            ))
+            .color(SECONDARY_TEXT)
+            .size(13.0),
+        );
+    }
+
+    let needle = view.search.trim().to_lowercase();
+    let matches: Vec<&String> = view
+        .entries
+        .iter()
+        .filter(|entry| needle.is_empty() || entry.to_lowercase().contains(&needle))
+        .collect();
+
+    ui.label(
+        egui::RichText::new(format!(
+            "{} / {} {}",
+            matches.len(),
+            view.entries.len(),
+            text("archive-entries", "entries")
+        ))
+        .color(SECONDARY_TEXT)
+        .size(12.0),
+    );
+    ui.add_space(4.0);
+
+    // Archives grow to thousands of lines, so only visible rows are laid out
+    let locked = view.locked;
+    let row_height = ui.spacing().interact_size.y;
+    egui::ScrollArea::vertical()
+        .id_source("archive_entries")
+        .auto_shrink([false, false])
+        .show_rows(ui, row_height, matches.len(), |ui, rows| {
+            for entry in &matches[rows] {
+                ui.horizontal(|ui| {
+                    let (extractor, id) = entry.split_once(' ').unwrap_or(("", entry.as_str()));
+                    ui.label(egui::RichText::new(extractor).color(SECONDARY_TEXT));
+                    ui.label(egui::RichText::new(id).color(MAIN_TEXT).monospace());
+                    let remove = ui.add_enabled(
+                        !locked,
+                        egui::Button::new(text("remove-button", "Remove")).small(),
+                    );
+                    if remove.clicked() {
+                        action = Some(ArchiveAction::Remove(entry.to_string()));
+                    }
+                });
+            }
+        });
+
+    action
+}
@@ -0,0 +1,86 @@
+use eframe::egui;
+
+use crate::localizations::Localizations;
+use crate::models::{AppState, DownloadFormat};
+
+use crate::theme::*;
+
+// Toggles for cover art, tags, chapters and chapter splitting. Returns true
+// when the user asks to look for ffmpeg again, e.g. after installing it
+pub fn render_embed_options(ui: &mut egui::Ui, state: &mut AppState, localizer: &Localizations) -> bool {
+    let mut recheck = false;
+    egui::CollapsingHeader::new(
+        egui::RichText::new(
+            localizer
+                .lookup_single_language("embed-title", None)
+                .unwrap_or_else(|| "Cover art, tags and chapters".to_string()),
+        )
+        .color(MAIN_TEXT)
+        .size(14.0),
+    )
+    .id_source("embed_options")
+    .show(ui, |ui| {
+        let embed = &mut state.settings.embed;
+        let toggles = [
+            (&mut embed.thumbnail, "embed-thumbnail", "Embed the thumbnail as cover art"),
+            (
+                &mut embed.metadata,
+                "embed-metadata",
+                "Write title, artist, album, date and description",
+            ),
+            (&mut embed.chapters, "embed-chapters", "Embed chapters"),
+        ];
+        for (value, key, fallback) in toggles {
+            ui.checkbox(
+                value,
+                egui::RichText::new(
+                    localizer
+                        .lookup_single_language(key, None)
+                        .unwrap_or_else(|| fallback.to_string()),
+                )
+                .color(MAIN_TEXT)
+                .size(14.0),
+            );
+        }
+
+        ui.checkbox(
+            &mut state.settings.split_chapters,
+            egui::RichText::new(
+                localizer
+                    .lookup_single_language("split-chapters", None)
+                    .unwrap_or_else(|| "Also save each chapter as its own file".to_string()),
+            )
+            .color(MAIN_TEXT)
+            .size(14.0),
+        );
+
+        // MP3 extraction and subtitles picked in their own section need it too
+        let needs_ffmpeg = state.settings.embed.any()
+            || state.settings.split_chapters
+            || !state.settings.subtitles.languages.is_empty()
+            || state.format == DownloadFormat::MP3;
+        if needs_ffmpeg && !state.ffmpeg_available {
+            ui.label(
+                egui::RichText::new(
+                    localizer
+                        .lookup_single_language("embed-ffmpeg-missing", None)
+                        .unwrap_or_else(|| {
+                            "ffmpeg was not found, so MP3 downloads will fail and others will be \
+                             saved without these or embedded and converted subtitles. Install \
+                             ffmpeg (e.g. sudo apt install ffmpeg) to use them."
+                                .to_string()
+                        }),
+                )
+                .color(TEXT_ERROR)
+                .size(13.0),
+            );
+            let recheck_label = localizer
+                .lookup_single_language("embed-recheck", None)
+                .unwrap_or_else(|| "Check again".to_string());
+            if ui.small_button(recheck_label).clicked() {
+                recheck = true;
+            }
+        }
+    });
+    recheck
+}
@@ -0,0 +1,223 @@
+use eframe::egui;
+use std::cmp::Ordering;
+
+use crate::localizations::Localizations;
+use crate::models::{AppState, FormatColumn, FormatInfo, FormatSelection};
+use crate::progress::format_bytes;
+
+use crate::theme::*;
+
+const COLUMNS: [(FormatColumn, &str, &str); 8] = [
+    (FormatColumn::Id, "format-column-id", "ID"),
+    (FormatColumn::Ext, "format-column-ext", "Container"),
+    (FormatColumn::Resolution, "format-column-resolution", "Resolution"),
+    (FormatColumn::Fps, "format-column-fps", "FPS"),
+    (FormatColumn::VideoCodec, "format-column-vcodec", "Video codec"),
+    (FormatColumn::AudioCodec, "format-column-acodec", "Audio codec"),
+    (FormatColumn::Bitrate, "format-column-bitrate", "Bitrate"),
+    (FormatColumn::Size, "format-column-size", "Size"),
+];
+
+fn compare(a: &FormatInfo, b: &FormatInfo, column: FormatColumn) -> Ordering {
+    match column {
+        FormatColumn::Id => a.format_id.cmp(&b.format_id),
+        FormatColumn::Ext => a.ext.cmp(&b.ext),
+        FormatColumn::Resolution => (a.height, a.width).cmp(&(b.height, b.width)),
+        FormatColumn::Fps => a.fps.partial_cmp(&b.fps).unwrap_or(Ordering::Equal),
+        FormatColumn::VideoCodec => a.vcodec.cmp(&b.vcodec),
+        FormatColumn::AudioCodec => a.acodec.cmp(&b.acodec),
+        FormatColumn::Bitrate => a.tbr.partial_cmp(&b.tbr).unwrap_or(Ordering::Equal),
+        FormatColumn::Size => a.size().cmp(&b.size()),
+    }
+}
+
+// yt-dlp reports a missing stream as the "none" codec
+fn codec_text(codec: &Option<String>) -> String {
+    match codec.as_deref() {
+        Some("none") | None => "—".to_string(),
+        Some(codec) => codec.to_string(),
+    }
+}
+
+fn cell_text(format: &FormatInfo, column: FormatColumn, localizer: &Localizations) -> String {
+    match column {
+        FormatColumn::Id => format.format_id.clone(),
+        FormatColumn::Ext => format.ext.clone().unwrap_or_default(),
+        FormatColumn::Resolution => {
+            if !format.has_video() {
+                localizer
+                    .lookup_single_language("audio-only", None)
+                    .unwrap_or_else(|| "audio only".to_string())
+            } else {
+                format.resolution.clone().unwrap_or_default()
+            }
+        }
+        FormatColumn::Fps => format.fps.map(|fps| format!("{}", fps)).unwrap_or_default(),
+        FormatColumn::VideoCodec => codec_text(&format.vcodec),
+        FormatColumn::AudioCodec => codec_text(&format.acodec),
+        FormatColumn::Bitrate => format
+            .tbr
+            .map(|tbr| format!("{:.0} kbit/s", tbr))
+            .unwrap_or_default(),
+        FormatColumn::Size => format.size().map(format_bytes).unwrap_or_default(),
+    }
+}
+
+// Picking a video stream keeps the chosen audio unless the new stream
+// already carries audio; picking an audio-only stream drops a combined one
+fn toggle(selection: &mut FormatSelection, format: &FormatInfo, formats: &[FormatInfo]) {
+    let id = format.format_id.clone();
+    if format.has_video() {
+        if selection.video.as_ref() == Some(&id) {
+            selection.video = None;
+            return;
+        }
+        selection.video = Some(id);
+        if format.has_audio() {
+            selection.audio = None;
+        }
+    } else if selection.audio.as_ref() == Some(&id) {
+        selection.audio = None;
+    } else {
+        selection.audio = Some(id);
+        let combined = selection.video.as_ref().is_some_and(|video| {
+            formats
+                .iter()
+                .any(|f| &f.format_id == video && f.has_audio())
+        });
+        if combined {
+            selection.video = None;
+        }
+    }
+}
+
+// Dialog listing every format yt-dlp reported for the previewed URL.
+// Clicking a column header sorts by it, clicking again flips the order
+pub fn render_format_chooser(ctx: &egui::Context, state: &mut AppState, localizer: &Localizations) {
+    let Some(preview) = state.preview.as_mut() else {
+        return;
+    };
+    let Some(metadata) = &preview.metadata else {
+        return;
+    };
+    let chooser = &mut preview.chooser;
+    if !chooser.open {
+        return;
+    }
+
+    // Stable sort, so equal rows keep yt-dlp's own order
+    let mut formats: Vec<&FormatInfo> = metadata.formats.iter().collect();
+    formats.sort_by(|a, b| {
+        let ordering = compare(a, b, chooser.sort_by);
+        if chooser.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+
+    let mut open = true;
+    let mut apply = false;
+    egui::Window::new(
+        localizer
+            .lookup_single_language("format-chooser-title", None)
+            .unwrap_or_else(|| "Choose format".to_string()),
+    )
+    .open(&mut open)
+    .collapsible(false)
+    .default_size(egui::vec2(600.0, 400.0))
+    .show(ctx, |ui| {
+        ui.label(
+            egui::RichText::new(
+                localizer
+                    .lookup_single_language("format-chooser-hint", None)
+                    .unwrap_or_else(|| {
+                        "Pick a video stream and an audio stream, or a single stream that has both"
+                            .to_string()
+                    }),
+            )
+            .color(SECONDARY_TEXT)
+            .size(13.0),
+        );
+        ui.add_space(6.0);
+
+        egui::ScrollArea::both().max_height(300.0).show(ui, |ui| {
+            egui::Grid::new("format_table")
+                .striped(true)
+                .spacing(egui::vec2(12.0, 4.0))
+                .show(ui, |ui| {
+                    for (column, key, fallback) in COLUMNS {
+                        let mut title = localizer
+                            .lookup_single_language(key, None)
+                            .unwrap_or_else(|| fallback.to_string());
+                        if chooser.sort_by == column {
+                            title.push_str(if chooser.descending { " ⏷" } else { " ⏶" });
+                        }
+                        if ui
+                            .add(egui::Button::new(egui::RichText::new(title).strong()).frame(false))
+                            .clicked()
+                        {
+                            if chooser.sort_by == column {
+                                chooser.descending = !chooser.descending;
+                            } else {
+                                chooser.sort_by = column;
+                                chooser.descending = false;
+                            }
+                        }
+                    }
+                    ui.end_row();
+
+                    for format in &formats {
+                        let id = Some(&format.format_id);
+                        let selected = chooser.selection.video.as_ref() == id
+                            || chooser.selection.audio.as_ref() == id;
+                        if ui.selectable_label(selected, &format.format_id).clicked() {
+                            toggle(&mut chooser.selection, format, &metadata.formats);
+                        }
+                        for (column, _, _) in &COLUMNS[1..] {
+                            ui.label(cell_text(format, *column, localizer));
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+
+        ui.add_space(6.0);
+        ui.horizontal(|ui| {
+            let spec = chooser.selection.spec();
+            ui.label(
+                egui::RichText::new(format!(
+                    "{} {}",
+                    localizer
+                        .lookup_single_language("custom-format-label", None)
+                        .unwrap_or_else(|| "Format:".to_string()),
+                    spec.as_deref().unwrap_or("—")
+                ))
+                .color(MAIN_TEXT),
+            );
+            if let Some(size) = metadata.selection_size(&chooser.selection) {
+                ui.label(egui::RichText::new(format!("~{}", format_bytes(size))).color(SECONDARY_TEXT));
+            }
+
+            if ui
+                .add_enabled(
+                    spec.is_some(),
+                    egui::Button::new(
+                        localizer
+                            .lookup_single_language("use-format", None)
+                            .unwrap_or_else(|| "Use this format".to_string()),
+                    ),
+                )
+                .clicked()
+            {
+                apply = true;
+            }
+        });
+    });
+
+    if apply {
+        preview.selected_format = Some(preview.chooser.selection.clone());
+        open = false;
+    }
+    preview.chooser.open = open;
+}
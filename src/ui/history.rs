@@ -0,0 +1,268 @@
+use eframe::egui::{self, Stroke};
+
+use crate::history::{HistoryEntry, HistoryId, Outcome, Period};
+use crate::localizations::Localizations;
+use crate::models::{AppState, DownloadFormat};
+use crate::progress::{format_bytes, format_duration, format_timestamp};
+use crate::storage::unix_now;
+
+use crate::theme::*;
+
+// Fits every line an entry can have: title, details, URL, path, error and buttons
+const ENTRY_HEIGHT: f32 = 136.0;
+
+pub enum HistoryAction {
+    // Queue again with the same settings
+    Rerun(HistoryId),
+    // Load into the Downloads tab to change settings first
+    Edit(HistoryId),
+    Delete(HistoryId),
+    Open(HistoryId),
+    Reveal(HistoryId),
+    ExportJson,
+    ExportCsv,
+    Import,
+}
+
+fn outcome_label(outcome: Outcome, localizer: &Localizations) -> String {
+    let (key, fallback) = match outcome {
+        Outcome::Completed => ("outcome-completed", "Completed"),
+        Outcome::Failed => ("outcome-failed", "Failed"),
+        Outcome::Cancelled => ("outcome-cancelled", "Cancelled"),
+    };
+    localizer
+        .lookup_single_language(key, None)
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+fn period_label(period: Period, localizer: &Localizations) -> String {
+    let (key, fallback) = match period {
+        Period::Any => ("period-any", "Any time"),
+        Period::Day => ("period-day", "Last 24 hours"),
+        Period::Week => ("period-week", "Last 7 days"),
+        Period::Month => ("period-month", "Last 30 days"),
+    };
+    localizer
+        .lookup_single_language(key, None)
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+fn render_filters(ui: &mut egui::Ui, state: &mut AppState, localizer: &Localizations) {
+    let text = |key: &str, fallback: &str| {
+        localizer
+            .lookup_single_language(key, None)
+            .unwrap_or_else(|| fallback.to_string())
+    };
+    let all = text("filter-all", "All");
+    let filter = &mut state.history_filter;
+
+    ui.add(
+        egui::TextEdit::singleline(&mut filter.search)
+            .hint_text(text("history-search", "Search URL, title, site, path or error"))
+            .desired_width(f32::INFINITY),
+    );
+
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_source("history_outcome")
+            .selected_text(filter.outcome.map_or(all.clone(), |o| outcome_label(o, localizer)))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut filter.outcome, None, all.clone());
+                for outcome in [Outcome::Completed, Outcome::Failed, Outcome::Cancelled] {
+                    ui.selectable_value(&mut filter.outcome, Some(outcome), outcome_label(outcome, localizer));
+                }
+            });
+
+        let format_label = |format: DownloadFormat| match format {
+            DownloadFormat::MP4 => "MP4",
+            DownloadFormat::MP3 => "MP3",
+        };
+        egui::ComboBox::from_id_source("history_format")
+            .selected_text(filter.format.map_or(all.clone(), |f| format_label(f).to_string()))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut filter.format, None, all.clone());
+                for format in [DownloadFormat::MP4, DownloadFormat::MP3] {
+                    ui.selectable_value(&mut filter.format, Some(format), format_label(format));
+                }
+            });
+
+        egui::ComboBox::from_id_source("history_period")
+            .selected_text(period_label(filter.period, localizer))
+            .show_ui(ui, |ui| {
+                for period in [Period::Any, Period::Day, Period::Week, Period::Month] {
+                    ui.selectable_value(&mut filter.period, period, period_label(period, localizer));
+                }
+            });
+    });
+}
+
+fn render_entry(
+    ui: &mut egui::Ui,
+    entry: &HistoryEntry,
+    localizer: &Localizations,
+    action: &mut Option<HistoryAction>,
+) {
+    let text = |key: &str, fallback: &str| {
+        localizer
+            .lookup_single_language(key, None)
+            .unwrap_or_else(|| fallback.to_string())
+    };
+
+    egui::Frame::none()
+        .rounding(ROUNDING_FRAME)
+        .stroke(Stroke::new(1.0, BORDER_COLOR))
+        .inner_margin(egui::vec2(8.0, 6.0))
+        .show(ui, |ui| {
+            // Fills the row; long lines are cut short so the row never grows
+            ui.set_width(ui.available_width());
+            ui.set_min_height(ui.available_height());
+            ui.add(
+                egui::Label::new(
+                    egui::RichText::new(entry.title.as_deref().unwrap_or(&entry.url))
+                        .color(MAIN_TEXT)
+                        .size(14.0)
+                        .strong(),
+                )
+                .truncate(true),
+            );
+
+            let outcome_color = match entry.outcome {
+                Outcome::Completed => PRIMARY_COLOR,
+                Outcome::Failed => TEXT_ERROR,
+                Outcome::Cancelled => SECONDARY_TEXT,
+            };
+            let mut details = vec![format_timestamp(entry.finished_at)];
+            if let Some(extractor) = &entry.extractor {
+                details.push(extractor.clone());
+            }
+            details.push(match entry.format {
+                DownloadFormat::MP4 => "MP4".to_string(),
+                DownloadFormat::MP3 => "MP3".to_string(),
+            });
+            if let Some(duration) = entry.duration {
+                details.push(format_duration(duration as u64));
+            }
+            if let Some(size) = entry.size {
+                details.push(format_bytes(size));
+            }
+            ui.horizontal(|ui| {
+                ui.label(
+                    egui::RichText::new(outcome_label(entry.outcome, localizer))
+                        .color(outcome_color)
+                        .size(12.0),
+                );
+                ui.label(
+                    egui::RichText::new(details.join(" • "))
+                        .color(SECONDARY_TEXT)
+                        .size(12.0),
+                );
+            });
+
+            if entry.title.is_some() {
+                ui.add(
+                    egui::Label::new(egui::RichText::new(&entry.url).color(SECONDARY_TEXT).size(12.0))
+                        .truncate(true),
+                );
+            }
+            if let Some(path) = &entry.output_path {
+                let label = ui.add(
+                    egui::Label::new(
+                        egui::RichText::new(path.display().to_string())
+                            .color(SECONDARY_TEXT)
+                            .size(12.0),
+                    )
+                    .truncate(true),
+                );
+                if entry.output_paths.len() > 1 {
+                    let paths = entry
+                        .output_paths
+                        .iter()
+                        .map(|path| path.display().to_string())
+                        .collect::<Vec<_>>();
+                    label.on_hover_text(paths.join("\n"));
+                }
+            }
+            if let Some(error) = &entry.error {
+                ui.add(egui::Label::new(egui::RichText::new(error).color(TEXT_ERROR).size(12.0)).truncate(true))
+                    .on_hover_text(error);
+            }
+
+            ui.horizontal(|ui| {
+                if ui.small_button(text("download-again", "Download again")).clicked() {
+                    *action = Some(HistoryAction::Rerun(entry.id));
+                }
+                if ui.small_button(text("edit-and-download", "Change settings…")).clicked() {
+                    *action = Some(HistoryAction::Edit(entry.id));
+                }
+                if entry.output_path.is_some() {
+                    if ui.small_button(text("open-file", "Open")).clicked() {
+                        *action = Some(HistoryAction::Open(entry.id));
+                    }
+                    if ui.small_button(text("show-in-folder", "Show in folder")).clicked() {
+                        *action = Some(HistoryAction::Reveal(entry.id));
+                    }
+                }
+                if ui.small_button(text("delete-button", "Delete")).clicked() {
+                    *action = Some(HistoryAction::Delete(entry.id));
+                }
+            });
+        });
+}
+
+// History tab: newest downloads first, narrowed down by the filters
+pub fn render_history(
+    ui: &mut egui::Ui,
+    state: &mut AppState,
+    localizer: &Localizations,
+) -> Option<HistoryAction> {
+    let mut action = None;
+
+    render_filters(ui, state, localizer);
+    ui.add_space(6.0);
+
+    ui.horizontal(|ui| {
+        let text = |key: &str, fallback: &str| {
+            localizer
+                .lookup_single_language(key, None)
+                .unwrap_or_else(|| fallback.to_string())
+        };
+        if ui.button(text("export-json", "Export JSON...")).clicked() {
+            action = Some(HistoryAction::ExportJson);
+        }
+        if ui.button(text("export-csv", "Export CSV...")).clicked() {
+            action = Some(HistoryAction::ExportCsv);
+        }
+        if ui.button(text("import-json", "Import JSON...")).clicked() {
+            action = Some(HistoryAction::Import);
+        }
+    });
+    if let Some(message) = &state.history_message {
+        ui.label(egui::RichText::new(message).color(SECONDARY_TEXT).size(13.0));
+    }
+    ui.add_space(6.0);
+
+    let entries = state.history.matching(&state.history_filter, unix_now());
+
+    ui.label(
+        egui::RichText::new(format!(
+            "{} / {}",
+            entries.len(),
+            state.history.entries().len()
+        ))
+        .color(SECONDARY_TEXT)
+        .size(12.0),
+    );
+
+    // Only visible entries are laid out, so every one gets the same height
+    egui::ScrollArea::vertical()
+        .id_source("history_entries")
+        .auto_shrink([false, false])
+        .show_rows(ui, ENTRY_HEIGHT, entries.len(), |ui, rows| {
+            for entry in &entries[rows] {
+                let size = egui::vec2(ui.available_width(), ENTRY_HEIGHT);
+                let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+                ui.allocate_ui_at_rect(rect, |ui| render_entry(ui, entry, localizer, &mut action));
+            }
+        });
+
+    action
+}
@@ -1,13 +1,78 @@
-use arboard::Clipboard;
 use eframe::egui::{self, Color32, Stroke};
 use rfd::FileDialog;
 use std::path::Path;
+use std::time::Instant;
 
+use crate::errors::{ErrorKind, Remedy};
+use crate::events::Stage;
 use crate::localizations::Localizations;
-use crate::models::{AppState, DownloadFormat};
+use crate::models::{
+    AppState, Chapter, Collision, CollisionPolicy, DownloadFormat, DownloadJob, FormatSelection,
+    JobId, JobStatus, PlaylistPreview, Tab, MAX_PARALLEL_DOWNLOADS, MAX_RETRY_ATTEMPTS,
+    MAX_RETRY_DELAY_SECS,
+};
+use crate::progress::{format_bytes, format_duration, format_speed};
+use crate::queue::DownloadQueue;
 
 use crate::theme::*;
 
+mod archive;
+mod embed_options;
+mod format_chooser;
+mod history;
+mod subscriptions;
+mod subtitle_options;
+mod template_editor;
+
+pub use archive::{render_archive, ArchiveAction};
+pub use embed_options::render_embed_options;
+pub use format_chooser::render_format_chooser;
+pub use history::{render_history, HistoryAction};
+pub use subscriptions::{render_subscriptions, SubscriptionAction};
+pub use subtitle_options::render_subtitle_options;
+pub use template_editor::render_template_editor;
+
+pub fn render_tabs(ui: &mut egui::Ui, state: &mut AppState, localizer: &Localizations) {
+    let new_count: usize = state
+        .subscriptions
+        .subscriptions()
+        .iter()
+        .map(|subscription| subscription.new_count)
+        .sum();
+    let mut subscriptions_label = localizer
+        .lookup_single_language("tab-subscriptions", None)
+        .unwrap_or_else(|| "Subscriptions".to_string());
+    if new_count > 0 {
+        subscriptions_label.push_str(&format!(" ({})", new_count));
+    }
+
+    ui.horizontal(|ui| {
+        ui.selectable_value(
+            &mut state.tab,
+            Tab::Downloads,
+            localizer
+                .lookup_single_language("tab-downloads", None)
+                .unwrap_or_else(|| "Downloads".to_string()),
+        );
+        ui.selectable_value(&mut state.tab, Tab::Subscriptions, subscriptions_label);
+        ui.selectable_value(
+            &mut state.tab,
+            Tab::History,
+            localizer
+                .lookup_single_language("tab-history", None)
+                .unwrap_or_else(|| "History".to_string()),
+        );
+        ui.selectable_value(
+            &mut state.tab,
+            Tab::Archive,
+            localizer
+                .lookup_single_language("tab-archive", None)
+                .unwrap_or_else(|| "Archive".to_string()),
+        );
+    });
+    ui.separator();
+}
+
 pub fn render_url_input(
     ui: &mut egui::Ui,
     state: &mut AppState,
@@ -56,6 +121,345 @@ pub fn render_url_input(
     .inner
 }
 
+pub enum PreviewAction {
+    Fetch,
+    // Queue the ticked playlist entries
+    Enqueue,
+}
+
+// Checklist of a flat-extracted playlist. Returns true when the user asked to
+// queue the ticked entries
+fn render_playlist(ui: &mut egui::Ui, playlist: &mut PlaylistPreview, localizer: &Localizations) -> bool {
+    let mut enqueue = false;
+    let count = playlist.info.entries.len();
+
+    egui::Frame::none()
+        .rounding(ROUNDING_FRAME)
+        .stroke(Stroke::new(1.0, BORDER_COLOR))
+        .inner_margin(egui::vec2(8.0, 6.0))
+        .show(ui, |ui| {
+            ui.label(
+                egui::RichText::new(format!(
+                    "{} ({} {})",
+                    playlist.info.title.as_deref().unwrap_or(&playlist.info.id),
+                    count,
+                    localizer
+                        .lookup_single_language("playlist-entries", None)
+                        .unwrap_or_else(|| "entries".to_string())
+                ))
+                .color(MAIN_TEXT)
+                .size(15.0)
+                .strong(),
+            );
+
+            ui.horizontal(|ui| {
+                if ui
+                    .small_button(
+                        localizer
+                            .lookup_single_language("select-all", None)
+                            .unwrap_or_else(|| "Select all".to_string()),
+                    )
+                    .clicked()
+                {
+                    playlist.selected.iter_mut().for_each(|selected| *selected = true);
+                }
+                if ui
+                    .small_button(
+                        localizer
+                            .lookup_single_language("select-none", None)
+                            .unwrap_or_else(|| "Select none".to_string()),
+                    )
+                    .clicked()
+                {
+                    playlist.selected.iter_mut().for_each(|selected| *selected = false);
+                }
+
+                ui.separator();
+                ui.label(
+                    localizer
+                        .lookup_single_language("items-label", None)
+                        .unwrap_or_else(|| "Items".to_string()),
+                );
+                // Each bound stops at the other, so the range never runs backwards
+                let last = count.max(1);
+                let start_bounds = 1..=playlist.range_end.min(last);
+                ui.add(egui::DragValue::new(&mut playlist.range_start).clamp_range(start_bounds));
+                ui.label("–");
+                let end_bounds = playlist.range_start.max(1)..=last;
+                ui.add(egui::DragValue::new(&mut playlist.range_end).clamp_range(end_bounds));
+                if ui
+                    .small_button(
+                        localizer
+                            .lookup_single_language("select-range", None)
+                            .unwrap_or_else(|| "Select range".to_string()),
+                    )
+                    .clicked()
+                {
+                    let (start, end) = (playlist.range_start, playlist.range_end);
+                    let range = start.min(end)..=start.max(end);
+                    for (index, selected) in playlist.selected.iter_mut().enumerate() {
+                        *selected = range.contains(&(index + 1));
+                    }
+                }
+            });
+
+            egui::ScrollArea::vertical()
+                .id_source("playlist_entries")
+                .max_height(180.0)
+                .show(ui, |ui| {
+                    for (index, entry) in playlist.info.entries.iter().enumerate() {
+                        let mut text = format!(
+                            "{}. {}",
+                            index + 1,
+                            entry.title.as_deref().unwrap_or(&entry.id)
+                        );
+                        if let Some(duration) = entry.duration {
+                            text.push_str(&format!(" ({})", format_duration(duration as u64)));
+                        }
+                        ui.add_enabled(
+                            entry.url.is_some(),
+                            egui::Checkbox::new(&mut playlist.selected[index], text),
+                        );
+                    }
+                });
+
+            let selected = playlist.selected_entries().count();
+            if ui
+                .add_enabled(
+                    selected > 0,
+                    egui::Button::new(format!(
+                        "{} ({})",
+                        localizer
+                            .lookup_single_language("enqueue-selected", None)
+                            .unwrap_or_else(|| "Add selected to queue".to_string()),
+                        selected
+                    )),
+                )
+                .clicked()
+            {
+                enqueue = true;
+            }
+        });
+
+    enqueue
+}
+
+// Shows the "Fetch info" button, then the metadata card or the playlist
+// checklist once fetched
+pub fn render_preview(
+    ui: &mut egui::Ui,
+    state: &mut AppState,
+    localizer: &Localizations,
+) -> Option<PreviewAction> {
+    let url = state.url.trim().to_string();
+    let format = state.format;
+    let mut preview = state.preview.as_mut().filter(|preview| preview.url == url);
+    let mut action = None;
+
+    ui.horizontal(|ui| {
+        let loading = preview.as_ref().is_some_and(|preview| preview.loading);
+        let fetch_label = localizer
+            .lookup_single_language("fetch-info", None)
+            .unwrap_or_else(|| "Fetch info".to_string());
+        if ui
+            .add_enabled(!loading && !url.is_empty(), egui::Button::new(fetch_label))
+            .clicked()
+        {
+            action = Some(PreviewAction::Fetch);
+        }
+        if loading {
+            ui.spinner();
+        }
+    });
+
+    let Some(preview) = preview.as_mut() else {
+        return action;
+    };
+
+    if let Some(error) = &preview.error {
+        ui.label(
+            egui::RichText::new(format!("Error: {}", error))
+                .color(TEXT_ERROR)
+                .size(13.0),
+        );
+    }
+
+    if let Some(playlist) = preview.playlist.as_mut() {
+        if render_playlist(ui, playlist, localizer) {
+            action = Some(PreviewAction::Enqueue);
+        }
+        return action;
+    }
+
+    let Some(metadata) = &preview.metadata else {
+        return action;
+    };
+
+    egui::Frame::none()
+        .rounding(ROUNDING_FRAME)
+        .stroke(Stroke::new(1.0, BORDER_COLOR))
+        .inner_margin(egui::vec2(8.0, 6.0))
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                if let Some(thumbnail) = &preview.thumbnail {
+                    ui.add(egui::Image::new(thumbnail).fit_to_exact_size(egui::vec2(160.0, 90.0)));
+                }
+
+                ui.vertical(|ui| {
+                    ui.label(
+                        egui::RichText::new(&metadata.title)
+                            .color(MAIN_TEXT)
+                            .size(15.0)
+                            .strong(),
+                    );
+
+                    let mut details = Vec::new();
+                    if let Some(uploader) = metadata.uploader_name() {
+                        details.push(uploader.to_string());
+                    }
+                    if let Some(duration) = metadata.duration {
+                        details.push(format_duration(duration as u64));
+                    }
+                    if let Some(date) = metadata.formatted_upload_date() {
+                        details.push(date);
+                    }
+                    if let Some(views) = metadata.view_count {
+                        details.push(format!(
+                            "{} {}",
+                            views,
+                            localizer
+                                .lookup_single_language("views-label", None)
+                                .unwrap_or_else(|| "views".to_string())
+                        ));
+                    }
+                    ui.label(
+                        egui::RichText::new(details.join(" • "))
+                            .color(SECONDARY_TEXT)
+                            .size(13.0),
+                    );
+
+                    let size = match &preview.selected_format {
+                        Some(selection) => metadata.selection_size(selection),
+                        None => metadata.estimated_size(format),
+                    };
+                    if let Some(size) = size {
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "{} ~{}",
+                                localizer
+                                    .lookup_single_language("estimated-size", None)
+                                    .unwrap_or_else(|| "Estimated size:".to_string()),
+                                format_bytes(size)
+                            ))
+                            .color(SECONDARY_TEXT)
+                            .size(13.0),
+                        );
+                    }
+
+                    ui.horizontal(|ui| {
+                        if ui
+                            .button(
+                                localizer
+                                    .lookup_single_language("choose-format", None)
+                                    .unwrap_or_else(|| "Choose format…".to_string()),
+                            )
+                            .clicked()
+                        {
+                            preview.chooser.open = true;
+                        }
+
+                        let spec = preview
+                            .selected_format
+                            .as_ref()
+                            .and_then(FormatSelection::spec);
+                        if let Some(spec) = spec {
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "{} {}",
+                                    localizer
+                                        .lookup_single_language("custom-format-label", None)
+                                        .unwrap_or_else(|| "Format:".to_string()),
+                                    spec
+                                ))
+                                .color(SECONDARY_TEXT)
+                                .size(13.0),
+                            );
+                            if ui
+                                .small_button(
+                                    localizer
+                                        .lookup_single_language("reset-format", None)
+                                        .unwrap_or_else(|| "Reset".to_string()),
+                                )
+                                .clicked()
+                            {
+                                preview.selected_format = None;
+                                preview.chooser.selection = FormatSelection::default();
+                            }
+                        }
+                    });
+                });
+            });
+
+            if !metadata.chapters.is_empty() {
+                render_chapters(ui, &metadata.chapters, localizer);
+            }
+        });
+
+    action
+}
+
+fn render_chapters(ui: &mut egui::Ui, chapters: &[Chapter], localizer: &Localizations) {
+    egui::CollapsingHeader::new(
+        egui::RichText::new(format!(
+            "{} ({})",
+            localizer
+                .lookup_single_language("chapters-title", None)
+                .unwrap_or_else(|| "Chapters".to_string()),
+            chapters.len()
+        ))
+        .color(MAIN_TEXT)
+        .size(13.0),
+    )
+    .id_source("preview_chapters")
+    .show(ui, |ui| {
+        egui::ScrollArea::vertical()
+            .id_source("preview_chapter_list")
+            .max_height(160.0)
+            .show(ui, |ui| {
+                egui::Grid::new("chapter_table")
+                    .striped(true)
+                    .spacing(egui::vec2(12.0, 2.0))
+                    .show(ui, |ui| {
+                        for (index, chapter) in chapters.iter().enumerate() {
+                            ui.label(
+                                egui::RichText::new(format!("{:02}", index + 1))
+                                    .color(SECONDARY_TEXT)
+                                    .size(12.0),
+                            );
+                            ui.label(
+                                egui::RichText::new(format_duration(chapter.start_time as u64))
+                                    .color(SECONDARY_TEXT)
+                                    .size(12.0),
+                            );
+                            ui.label(
+                                egui::RichText::new(chapter.title.as_deref().unwrap_or("—"))
+                                    .color(MAIN_TEXT)
+                                    .size(12.0),
+                            );
+                            ui.label(
+                                egui::RichText::new(format_duration(
+                                    (chapter.end_time - chapter.start_time).max(0.0) as u64,
+                                ))
+                                .color(SECONDARY_TEXT)
+                                .size(12.0),
+                            );
+                            ui.end_row();
+                        }
+                    });
+            });
+    });
+}
+
 pub fn render_format_selector(ui: &mut egui::Ui, state: &mut AppState, localizer: &Localizations) {
     ui.vertical(|ui| {
         ui.label(
@@ -178,71 +582,766 @@ pub fn render_download_dir_selector(
     changed
 }
 
-pub fn render_status(ui: &mut egui::Ui, state: &AppState, localizer: &Localizations) {
+pub fn render_queue_settings(ui: &mut egui::Ui, state: &mut AppState, localizer: &Localizations) {
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new(
+                localizer
+                    .lookup_single_language("parallel-downloads", None)
+                    .unwrap_or_else(|| "Parallel downloads:".to_string()),
+            )
+            .color(MAIN_TEXT)
+            .size(14.0),
+        );
+
+        ui.add(egui::Slider::new(
+            &mut state.settings.max_concurrent,
+            1..=MAX_PARALLEL_DOWNLOADS,
+        ));
+    });
+
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new(
+                localizer
+                    .lookup_single_language("cookies-label", None)
+                    .unwrap_or_else(|| "Cookies file:".to_string()),
+            )
+            .color(MAIN_TEXT)
+            .size(14.0),
+        );
+
+        match &state.settings.cookies_file {
+            Some(path) => {
+                ui.label(
+                    egui::RichText::new(path)
+                        .color(SECONDARY_TEXT)
+                        .size(13.0),
+                );
+                let clear_label = localizer
+                    .lookup_single_language("clear-button", None)
+                    .unwrap_or_else(|| "Clear".to_string());
+                if ui.small_button(clear_label).clicked() {
+                    state.settings.cookies_file = None;
+                }
+            }
+            None => {
+                let choose_label = localizer
+                    .lookup_single_language("choose-button", None)
+                    .unwrap_or_else(|| "Choose...".to_string());
+                if ui.small_button(choose_label).clicked() {
+                    if let Some(path) = FileDialog::new()
+                        .add_filter("cookies.txt", &["txt"])
+                        .pick_file()
+                    {
+                        state.settings.cookies_file = Some(path.to_string_lossy().to_string());
+                    }
+                }
+            }
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new(
+                localizer
+                    .lookup_single_language("retry-attempts", None)
+                    .unwrap_or_else(|| "Max attempts:".to_string()),
+            )
+            .color(MAIN_TEXT)
+            .size(14.0),
+        );
+        ui.add(egui::Slider::new(
+            &mut state.settings.retry.max_attempts,
+            1..=MAX_RETRY_ATTEMPTS,
+        ));
+
+        ui.label(
+            egui::RichText::new(
+                localizer
+                    .lookup_single_language("retry-delay", None)
+                    .unwrap_or_else(|| "First retry after (s):".to_string()),
+            )
+            .color(MAIN_TEXT)
+            .size(14.0),
+        );
+        ui.add(egui::Slider::new(
+            &mut state.settings.retry.base_delay_secs,
+            1..=MAX_RETRY_DELAY_SECS,
+        ));
+    });
+
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new(
+                localizer
+                    .lookup_single_language("collision-label", None)
+                    .unwrap_or_else(|| "If the file exists:".to_string()),
+            )
+            .color(MAIN_TEXT)
+            .size(14.0),
+        );
+        let policies = [
+            (CollisionPolicy::Skip, "collision-skip", "Skip"),
+            (CollisionPolicy::Overwrite, "collision-overwrite", "Overwrite"),
+            (CollisionPolicy::Rename, "collision-rename", "Save under a new name"),
+        ];
+        for (policy, key, fallback) in policies {
+            ui.radio_value(
+                &mut state.settings.collision_policy,
+                policy,
+                egui::RichText::new(
+                    localizer
+                        .lookup_single_language(key, None)
+                        .unwrap_or_else(|| fallback.to_string()),
+                )
+                .color(MAIN_TEXT)
+                .size(14.0),
+            );
+        }
+    });
+
+    ui.checkbox(
+        &mut state.settings.delete_partial_on_cancel,
+        egui::RichText::new(
+            localizer
+                .lookup_single_language("delete-partial", None)
+                .unwrap_or_else(|| "Delete partial files when cancelling".to_string()),
+        )
+        .color(MAIN_TEXT)
+        .size(14.0),
+    );
+}
+
+// Existing files listed under a completed job before the rest are counted
+const MAX_COLLISIONS_SHOWN: usize = 3;
+
+// Names an existing file a completed job met and what became of it
+fn render_collision(
+    ui: &mut egui::Ui,
+    collision: &Collision,
+    output_path: Option<&Path>,
+    localizer: &Localizations,
+) {
+    let file_name = |path: &Path| {
+        path.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+    let (key, fallback, path) = match collision {
+        Collision::AlreadyPresent(existing) => {
+            ("collision-kept", "Existing file kept:", existing.as_path())
+        }
+        Collision::Overwritten(existing) => {
+            ("collision-replaced", "Existing file replaced:", existing.as_path())
+        }
+        // The new name is worth more than the old one here
+        Collision::Renamed(existing) => match output_path {
+            Some(output_path) => ("collision-renamed", "Name taken, saved as:", output_path),
+            None => (
+                "collision-renamed-id",
+                "Name taken, saved with its id added:",
+                existing.as_path(),
+            ),
+        },
+    };
+    ui.label(
+        egui::RichText::new(format!(
+            "{} {}",
+            localizer
+                .lookup_single_language(key, None)
+                .unwrap_or_else(|| fallback.to_string()),
+            file_name(path)
+        ))
+        .color(SECONDARY_TEXT)
+        .size(12.0),
+    );
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryAction {
+    ResumeAll,
+    Discard,
+}
+
+pub fn render_recovery_prompt(
+    ui: &mut egui::Ui,
+    state: &AppState,
+    localizer: &Localizations,
+) -> Option<RecoveryAction> {
+    if state.recovered_jobs.is_empty() {
+        return None;
+    }
+
+    let mut action = None;
+
     egui::Frame::none()
         .rounding(ROUNDING_FRAME)
+        .stroke(Stroke::new(1.0, PRIMARY_COLOR))
+        .inner_margin(egui::vec2(8.0, 6.0))
         .show(ui, |ui| {
-            ui.vertical(|ui| {
-                ui.add_space(12.0);
+            ui.label(
+                egui::RichText::new(format!(
+                    "{} {}",
+                    localizer
+                        .lookup_single_language("recovery-found", None)
+                        .unwrap_or_else(|| "Interrupted downloads found:".to_string()),
+                    state.recovered_jobs.len()
+                ))
+                .color(MAIN_TEXT)
+                .size(14.0),
+            );
 
-                let status_text = if let Some(error) = &state.last_error {
-                    egui::RichText::new(format!("Error: {}", error))
-                        .color(TEXT_ERROR)
-                        .size(14.0)
-                } else {
-                    egui::RichText::new(&state.status)
-                        .color(TEXT_SUCCESS)
-                        .size(14.0)
-                };
+            for job in &state.recovered_jobs {
+                ui.label(
+                    egui::RichText::new(&job.url)
+                        .color(SECONDARY_TEXT)
+                        .size(12.0),
+                );
+            }
+
+            ui.horizontal(|ui| {
+                let resume_label = localizer
+                    .lookup_single_language("recovery-resume", None)
+                    .unwrap_or_else(|| "Resume all".to_string());
+                if ui.button(resume_label).clicked() {
+                    action = Some(RecoveryAction::ResumeAll);
+                }
+
+                let discard_label = localizer
+                    .lookup_single_language("recovery-discard", None)
+                    .unwrap_or_else(|| "Discard".to_string());
+                if ui.button(discard_label).clicked() {
+                    action = Some(RecoveryAction::Discard);
+                }
+            });
+        });
+    ui.add_space(10.0);
+
+    action
+}
+
+pub enum JobAction {
+    Cancel(JobId),
+    Pause(JobId),
+    Resume(JobId),
+    Retry(JobId),
+    Remedy(JobId, Remedy),
+    Open(JobId),
+    Reveal(JobId),
+    Remove(JobId),
+    ClearFinished,
+}
+
+pub fn render_message(ui: &mut egui::Ui, state: &AppState, localizer: &Localizations) {
+    ui.add_space(12.0);
+
+    let status_text = if let Some(error) = &state.last_error {
+        egui::RichText::new(format!("Error: {}", error))
+            .color(TEXT_ERROR)
+            .size(14.0)
+    } else {
+        egui::RichText::new(&state.status)
+            .color(TEXT_SUCCESS)
+            .size(14.0)
+    };
+
+    ui.label(status_text);
+
+    if let Some((downloaded, total)) = state.updater_progress {
+        let fraction = total
+            .filter(|&total| total > 0)
+            .map(|total| downloaded as f32 / total as f32)
+            .unwrap_or(0.0);
+        let text = match total {
+            Some(total) => format!("{} / {}", format_bytes(downloaded), format_bytes(total)),
+            None => format_bytes(downloaded),
+        };
+        ui.add(
+            egui::ProgressBar::new(fraction)
+                .text(format!(
+                    "{} {}",
+                    localizer
+                        .lookup_single_language("status-updating", None)
+                        .unwrap_or_else(|| "Updating yt-dlp...".to_string()),
+                    text
+                ))
+                .fill(PRIMARY_COLOR),
+        );
+    }
+
+    ui.add_space(8.0);
+}
+
+fn stage_label(stage: Stage, localizer: &Localizations) -> String {
+    let (key, fallback) = match stage {
+        Stage::Downloading => ("stage-downloading", "Downloading"),
+        Stage::Video => ("stage-video", "Downloading video"),
+        Stage::Audio => ("stage-audio", "Downloading audio"),
+        Stage::Merging => ("stage-merging", "Merging video and audio"),
+        Stage::ExtractingAudio => ("stage-extracting", "Extracting audio"),
+        Stage::Embedding => ("stage-embedding", "Embedding metadata"),
+        Stage::Moving => ("stage-moving", "Moving files"),
+        Stage::PostProcessing => ("stage-processing", "Processing"),
+    };
+    localizer
+        .lookup_single_language(key, None)
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+fn is_download_stage(stage: Stage) -> bool {
+    matches!(stage, Stage::Downloading | Stage::Video | Stage::Audio)
+}
+
+// Builds the "12.3% • 4.1 MiB / 10.0 MiB • 1.2 MiB/s • ETA 00:07" line for
+// the stream currently being downloaded
+fn progress_text(job: &DownloadJob, localizer: &Localizations) -> String {
+    let mut parts = vec![format!("{:.1}%", job.stage_progress)];
+
+    // yt-dlp only estimates the size of fragmented streams
+    let total = job.total_bytes.map(|total| {
+        if job.total_is_estimate {
+            format!("~{}", format_bytes(total))
+        } else {
+            format_bytes(total)
+        }
+    });
+    match (job.downloaded_bytes, total) {
+        (Some(downloaded), Some(total)) => {
+            parts.push(format!("{} / {}", format_bytes(downloaded), total))
+        }
+        (Some(downloaded), None) => parts.push(format_bytes(downloaded)),
+        (None, Some(total)) => parts.push(total),
+        (None, None) => {}
+    }
+    if let Some((index, count)) = job.fragments {
+        parts.push(format!(
+            "{} {}/{}",
+            localizer
+                .lookup_single_language("fragment-label", None)
+                .unwrap_or_else(|| "Fragment".to_string()),
+            index,
+            count
+        ));
+    }
+    if let Some(speed) = job.speed {
+        parts.push(format_speed(speed));
+    }
+    if let Some(eta) = job.eta {
+        parts.push(format!(
+            "{} {}",
+            localizer
+                .lookup_single_language("eta-label", None)
+                .unwrap_or_else(|| "ETA".to_string()),
+            format_duration(eta)
+        ));
+    }
+
+    parts.join(" • ")
+}
+
+pub fn render_job_list(
+    ui: &mut egui::Ui,
+    queue: &DownloadQueue,
+    localizer: &Localizations,
+) -> Option<JobAction> {
+    let mut action = None;
+
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new(
+                localizer
+                    .lookup_single_language("queue-title", None)
+                    .unwrap_or_else(|| "Downloads".to_string()),
+            )
+            .color(MAIN_TEXT)
+            .size(14.0),
+        );
+
+        if queue.jobs().iter().any(|job| job.is_finished()) {
+            let clear_label = localizer
+                .lookup_single_language("clear-finished", None)
+                .unwrap_or_else(|| "Clear finished".to_string());
+            if ui.small_button(clear_label).clicked() {
+                action = Some(JobAction::ClearFinished);
+            }
+        }
+    });
+
+    if queue.jobs().is_empty() {
+        ui.label(
+            egui::RichText::new(
+                localizer
+                    .lookup_single_language("queue-empty", None)
+                    .unwrap_or_else(|| "No downloads yet".to_string()),
+            )
+            .color(SECONDARY_TEXT)
+            .size(13.0),
+        );
+        return action;
+    }
+
+    egui::ScrollArea::vertical()
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            for job in queue.jobs() {
+                egui::Frame::none()
+                    .rounding(ROUNDING_FRAME)
+                    .stroke(Stroke::new(1.0, BORDER_COLOR))
+                    .inner_margin(egui::vec2(8.0, 4.0))
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                egui::RichText::new(job.title.as_deref().unwrap_or(&job.url))
+                                    .color(MAIN_TEXT)
+                                    .size(13.0),
+                            );
+
+                            // Pausing a queued job holds it back until it is resumed
+                            if matches!(job.status, JobStatus::Queued | JobStatus::Downloading) {
+                                let pause_label = localizer
+                                    .lookup_single_language("pause-button", None)
+                                    .unwrap_or_else(|| "Pause".to_string());
+                                if ui.small_button(pause_label).clicked() {
+                                    action = Some(JobAction::Pause(job.id));
+                                }
+                            }
+
+                            if job.status == JobStatus::Paused {
+                                let resume_label = localizer
+                                    .lookup_single_language("resume-button", None)
+                                    .unwrap_or_else(|| "Resume".to_string());
+                                if ui.small_button(resume_label).clicked() {
+                                    action = Some(JobAction::Resume(job.id));
+                                }
+                            }
 
-                ui.label(status_text);
-                ui.add_space(8.0);
+                            if !job.is_finished() {
+                                let cancel_label = localizer
+                                    .lookup_single_language("cancel-button", None)
+                                    .unwrap_or_else(|| "Cancel".to_string());
+                                if ui.small_button(cancel_label).clicked() {
+                                    action = Some(JobAction::Cancel(job.id));
+                                }
+                            }
 
-                if state.is_downloading {
-                    let progress_text = if !state.download_speed.is_empty() {
-                        format!(
-                            "{} • {} • ETA: {}",
-                            state.status, state.download_speed, state.eta
+                            if job.is_finished() {
+                                let remove_label = localizer
+                                    .lookup_single_language("remove-button", None)
+                                    .unwrap_or_else(|| "Remove".to_string());
+                                if ui.small_button(remove_label).clicked() {
+                                    action = Some(JobAction::Remove(job.id));
+                                }
+                            }
+                        });
+
+                        render_status(ui, job, localizer, &mut action);
+                    });
+                ui.add_space(6.0);
+            }
+        });
+
+    action
+}
+
+pub fn render_status(
+    ui: &mut egui::Ui,
+    job: &DownloadJob,
+    localizer: &Localizations,
+    action: &mut Option<JobAction>,
+) {
+    ui.vertical(|ui| {
+        match &job.status {
+            JobStatus::Queued => match job.retry_at {
+                Some(retry_at) => {
+                    let seconds = retry_at
+                        .saturating_duration_since(Instant::now())
+                        .as_secs_f32()
+                        .ceil() as u64;
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{} {}s • {} {}",
+                            localizer
+                                .lookup_single_language("status-retrying", None)
+                                .unwrap_or_else(|| "Retrying in".to_string()),
+                            seconds,
+                            localizer
+                                .lookup_single_language("attempt-label", None)
+                                .unwrap_or_else(|| "attempt".to_string()),
+                            job.attempts + 1
+                        ))
+                        .color(SECONDARY_TEXT)
+                        .size(13.0),
+                    );
+                    if let Some(failure) = &job.last_failure {
+                        ui.label(
+                            egui::RichText::new(failure)
+                                .color(SECONDARY_TEXT)
+                                .size(12.0),
+                        );
+                    }
+                }
+                None => {
+                    ui.label(
+                        egui::RichText::new(
+                            localizer
+                                .lookup_single_language("status-queued", None)
+                                .unwrap_or_else(|| "Queued".to_string()),
                         )
-                    } else {
-                        localizer
-                            .lookup_single_language("status-downloading", None)
-                            .unwrap_or_else(|| "Starting download...".to_string())
-                    };
+                        .color(SECONDARY_TEXT)
+                        .size(13.0),
+                    );
+                }
+            },
+            JobStatus::Downloading => {
+                let stage_text = match job.stage {
+                    Some(stage) if is_download_stage(stage) => {
+                        format!("{}: {}", stage_label(stage, localizer), progress_text(job, localizer))
+                    }
+                    Some(stage) => format!("{}...", stage_label(stage, localizer)),
+                    None => localizer
+                        .lookup_single_language("status-downloading", None)
+                        .unwrap_or_else(|| "Starting download...".to_string()),
+                };
 
-                    // Progress bar with custom styling
-                    let progress_bar = egui::ProgressBar::new(state.progress / 100.0)
+                if let Some((index, count)) = job.item {
+                    // Whole run first, then the item being downloaded
+                    let mut batch_text = format!(
+                        "{} {} / {} • {:.0}% {}",
+                        localizer
+                            .lookup_single_language("item-label", None)
+                            .unwrap_or_else(|| "Item".to_string()),
+                        index,
+                        count,
+                        job.progress,
+                        localizer
+                            .lookup_single_language("total-label", None)
+                            .unwrap_or_else(|| "total".to_string())
+                    );
+                    if let Some(eta) = job.batch_eta() {
+                        batch_text.push_str(&format!(
+                            " • {} {}",
+                            localizer
+                                .lookup_single_language("eta-label", None)
+                                .unwrap_or_else(|| "ETA".to_string()),
+                            format_duration(eta.as_secs())
+                        ));
+                    }
+                    ui.add(
+                        egui::ProgressBar::new(job.progress / 100.0)
+                            .text(batch_text)
+                            .fill(PRIMARY_COLOR),
+                    );
+                    ui.add(
+                        egui::ProgressBar::new(job.item_progress / 100.0)
+                            .show_percentage()
+                            .fill(PRIMARY_COLOR),
+                    );
+                } else {
+                    // Overall progress across every stage of the job
+                    let progress_bar = egui::ProgressBar::new(job.progress / 100.0)
                         .show_percentage()
-                        .text(progress_text)
                         .fill(PRIMARY_COLOR);
-
                     ui.add(progress_bar);
+                }
 
-                    // Progress text below the bar
+                ui.label(
+                    egui::RichText::new(stage_text)
+                        .color(SECONDARY_TEXT)
+                        .size(12.0),
+                );
+            }
+            JobStatus::Paused => {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} • {:.1}%",
+                        localizer
+                            .lookup_single_language("status-paused", None)
+                            .unwrap_or_else(|| "Paused".to_string()),
+                        job.progress
+                    ))
+                    .color(SECONDARY_TEXT)
+                    .size(13.0),
+                );
+            }
+            JobStatus::Completed => {
+                // A playlist that downloaded some items still completed
+                let skipped = !job.collisions.is_empty()
+                    && job
+                        .collisions
+                        .iter()
+                        .all(|collision| matches!(collision, Collision::AlreadyPresent(_)))
+                    && (job.item.is_none() || job.output_path.is_none());
+                let (key, fallback) = if skipped {
+                    ("status-already-present", "Already present, skipped")
+                } else {
+                    ("download-complete", "Download complete")
+                };
+                ui.label(
+                    egui::RichText::new(
+                        localizer
+                            .lookup_single_language(key, None)
+                            .unwrap_or_else(|| fallback.to_string()),
+                    )
+                    .color(TEXT_SUCCESS)
+                    .size(13.0),
+                );
+                // The output only names the renamed file when there was one
+                let output_path = match job.collisions.len() {
+                    1 => job.output_path.as_deref(),
+                    _ => None,
+                };
+                for collision in job.collisions.iter().take(MAX_COLLISIONS_SHOWN) {
+                    render_collision(ui, collision, output_path, localizer);
+                }
+                if job.collisions.len() > MAX_COLLISIONS_SHOWN {
                     ui.label(
-                        egui::RichText::new(format!("{:.1}% complete", state.progress))
+                        egui::RichText::new(format!(
+                            "+{} {}",
+                            job.collisions.len() - MAX_COLLISIONS_SHOWN,
+                            localizer
+                                .lookup_single_language("collision-more", None)
+                                .unwrap_or_else(|| "more".to_string())
+                        ))
+                        .color(SECONDARY_TEXT)
+                        .size(12.0),
+                    );
+                }
+            }
+            JobStatus::Cancelled => {
+                ui.label(
+                    egui::RichText::new(
+                        localizer
+                            .lookup_single_language("status-cancelled", None)
+                            .unwrap_or_else(|| "Cancelled".to_string()),
+                    )
+                    .color(SECONDARY_TEXT)
+                    .size(13.0),
+                );
+            }
+            JobStatus::Failed(error) => {
+                let kind = job.error_kind.unwrap_or(ErrorKind::Unknown);
+                let (description_key, description) = kind.description();
+                let (hint_key, hint) = kind.hint();
+
+                ui.label(
+                    egui::RichText::new(
+                        localizer
+                            .lookup_single_language(description_key, None)
+                            .unwrap_or_else(|| description.to_string()),
+                    )
+                    .color(TEXT_ERROR)
+                    .size(13.0),
+                );
+                ui.label(
+                    egui::RichText::new(
+                        localizer
+                            .lookup_single_language(hint_key, None)
+                            .unwrap_or_else(|| hint.to_string()),
+                    )
+                    .color(SECONDARY_TEXT)
+                    .size(12.0),
+                );
+
+                ui.horizontal(|ui| {
+                    if let Some(remedy) = kind.remedy() {
+                        let (key, fallback) = match remedy {
+                            Remedy::UpdateYtdlp => ("update-ytdlp", "Update yt-dlp"),
+                            Remedy::ProvideCookies => ("provide-cookies", "Provide cookies"),
+                        };
+                        let label = localizer
+                            .lookup_single_language(key, None)
+                            .unwrap_or_else(|| fallback.to_string());
+                        if ui.small_button(label).clicked() {
+                            *action = Some(JobAction::Remedy(job.id, remedy));
+                        }
+                    }
+
+                    let retry_label = localizer
+                        .lookup_single_language("retry-button", None)
+                        .unwrap_or_else(|| "Retry".to_string());
+                    if ui.small_button(retry_label).clicked() {
+                        *action = Some(JobAction::Retry(job.id));
+                    }
+                });
+
+                egui::CollapsingHeader::new(
+                    egui::RichText::new(
+                        localizer
+                            .lookup_single_language("error-details", None)
+                            .unwrap_or_else(|| "Details".to_string()),
+                    )
+                    .color(SECONDARY_TEXT)
+                    .size(12.0),
+                )
+                .id_source(("job-error", job.id))
+                .show(ui, |ui| {
+                    ui.label(
+                        egui::RichText::new(error)
+                            .color(SECONDARY_TEXT)
+                            .size(12.0),
+                    );
+                });
+            }
+        }
+
+        if !job.warnings.is_empty() {
+            egui::CollapsingHeader::new(
+                egui::RichText::new(format!(
+                    "{} ({})",
+                    localizer
+                        .lookup_single_language("warnings-label", None)
+                        .unwrap_or_else(|| "Warnings".to_string()),
+                    job.warnings.len()
+                ))
+                .color(SECONDARY_TEXT)
+                .size(12.0),
+            )
+            .id_source(("job-warnings", job.id))
+            .show(ui, |ui| {
+                for warning in &job.warnings {
+                    ui.label(
+                        egui::RichText::new(warning)
                             .color(SECONDARY_TEXT)
                             .size(12.0),
                     );
                 }
+            });
+        }
 
-                if let Some(path) = &state.output_path {
-                    ui.add_space(12.0);
-                    ui.horizontal(|ui| {
-                        ui.label(
-                            egui::RichText::new("Saved to: ")
-                                .color(SECONDARY_TEXT)
-                                .size(13.0),
-                        );
-                        ui.label(
-                            egui::RichText::new(path.display().to_string())
-                                .color(PRIMARY_COLOR)
-                                .size(13.0),
-                        );
-                    });
+        if let Some(path) = &job.output_path {
+            ui.horizontal(|ui| {
+                ui.label(
+                    egui::RichText::new(
+                        localizer
+                            .lookup_single_language("saved-to", None)
+                            .unwrap_or_else(|| "Saved to:".to_string()),
+                    )
+                    .color(SECONDARY_TEXT)
+                    .size(13.0),
+                );
+                ui.label(
+                    egui::RichText::new(path.display().to_string())
+                        .color(PRIMARY_COLOR)
+                        .size(13.0),
+                );
+            });
+
+            ui.horizontal(|ui| {
+                let open_label = localizer
+                    .lookup_single_language("open-file", None)
+                    .unwrap_or_else(|| "Open".to_string());
+                if ui.small_button(open_label).clicked() {
+                    *action = Some(JobAction::Open(job.id));
                 }
 
-                ui.add_space(16.0);
+                let reveal_label = localizer
+                    .lookup_single_language("show-in-folder", None)
+                    .unwrap_or_else(|| "Show in folder".to_string());
+                if ui.small_button(reveal_label).clicked() {
+                    *action = Some(JobAction::Reveal(job.id));
+                }
             });
-        });
+        }
+    });
 }
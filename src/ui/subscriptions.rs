@@ -0,0 +1,270 @@
+use eframe::egui::{self, Stroke};
+use rfd::FileDialog;
+
+use crate::localizations::Localizations;
+use crate::models::{AppState, DownloadFormat};
+use crate::progress::format_duration;
+use crate::storage::unix_now;
+use crate::subscriptions::{
+    Subscription, SubscriptionId, MAX_CHECK_INTERVAL_MINUTES, MIN_CHECK_INTERVAL_MINUTES,
+};
+
+use crate::theme::*;
+
+pub enum SubscriptionAction {
+    Add,
+    Check(SubscriptionId),
+    Remove(SubscriptionId),
+}
+
+fn format_label(format: DownloadFormat, localizer: &Localizations) -> String {
+    match format {
+        DownloadFormat::MP4 => localizer
+            .lookup_single_language("format-mp4", None)
+            .unwrap_or_else(|| "MP4 (Video)".to_string()),
+        DownloadFormat::MP3 => localizer
+            .lookup_single_language("format-mp3", None)
+            .unwrap_or_else(|| "MP3 (Audio only)".to_string()),
+    }
+}
+
+fn render_form(
+    ui: &mut egui::Ui,
+    state: &mut AppState,
+    localizer: &Localizations,
+    action: &mut Option<SubscriptionAction>,
+) {
+    let text = |key: &str, fallback: &str| {
+        localizer
+            .lookup_single_language(key, None)
+            .unwrap_or_else(|| fallback.to_string())
+    };
+    let form = &mut state.subscription_form;
+
+    egui::CollapsingHeader::new(text("add-subscription", "Add subscription"))
+        .default_open(state.subscriptions.subscriptions().is_empty())
+        .show(ui, |ui| {
+            egui::Grid::new("subscription_form")
+                .num_columns(2)
+                .spacing(egui::vec2(10.0, 6.0))
+                .show(ui, |ui| {
+                    ui.label(text("subscription-url", "Channel or playlist URL"));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut form.url)
+                            .hint_text(text("subscription-url-hint", "e.g. a channel's Videos page")),
+                    );
+                    ui.end_row();
+
+                    ui.label(text("subscription-name", "Name"));
+                    ui.text_edit_singleline(&mut form.name);
+                    ui.end_row();
+
+                    ui.label(text("download-format", "Download as"));
+                    ui.horizontal(|ui| {
+                        for format in [DownloadFormat::MP4, DownloadFormat::MP3] {
+                            ui.radio_value(&mut form.format, format, format_label(format, localizer));
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label(text("download-to", "Download to"));
+                    ui.horizontal(|ui| {
+                        if form.download_dir.is_empty() {
+                            form.download_dir = state.download_dir.clone();
+                        }
+                        ui.text_edit_singleline(&mut form.download_dir);
+                        if ui.small_button(text("browse-button", "Browse...")).clicked() {
+                            if let Some(path) = FileDialog::new().pick_folder() {
+                                form.download_dir = path.to_string_lossy().to_string();
+                            }
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label(text("filter-title", "Title contains"));
+                    ui.text_edit_singleline(&mut form.filters.title_contains);
+                    ui.end_row();
+
+                    ui.label(text("filter-duration", "Duration (minutes, 0 = any)"));
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::DragValue::new(&mut form.filters.min_duration_minutes)
+                                .clamp_range(0..=1440),
+                        );
+                        ui.label("–");
+                        ui.add(
+                            egui::DragValue::new(&mut form.filters.max_duration_minutes)
+                                .clamp_range(0..=1440),
+                        );
+                    });
+                    ui.end_row();
+
+                    ui.label(text("filter-max-items", "Newest entries to check"));
+                    ui.add(egui::DragValue::new(&mut form.filters.max_items).clamp_range(1..=500));
+                    ui.end_row();
+                });
+
+            ui.checkbox(
+                &mut form.skip_existing,
+                text("skip-existing", "Only download uploads from now on"),
+            );
+
+            let add = egui::Button::new(text("add-button", "Add"));
+            if ui.add_enabled(!form.url.trim().is_empty(), add).clicked() {
+                *action = Some(SubscriptionAction::Add);
+            }
+        });
+}
+
+fn last_checked_text(subscription: &Subscription, localizer: &Localizations) -> String {
+    match subscription.last_checked {
+        Some(checked) => format!(
+            "{} {}",
+            localizer
+                .lookup_single_language("since-last-check", None)
+                .unwrap_or_else(|| "Since last check:".to_string()),
+            format_duration(unix_now().saturating_sub(checked))
+        ),
+        None => localizer
+            .lookup_single_language("never-checked", None)
+            .unwrap_or_else(|| "Not checked yet".to_string()),
+    }
+}
+
+fn render_subscription(
+    ui: &mut egui::Ui,
+    subscription: &Subscription,
+    localizer: &Localizations,
+    action: &mut Option<SubscriptionAction>,
+) {
+    egui::Frame::none()
+        .rounding(ROUNDING_FRAME)
+        .stroke(Stroke::new(1.0, BORDER_COLOR))
+        .inner_margin(egui::vec2(8.0, 6.0))
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            ui.horizontal(|ui| {
+                ui.label(
+                    egui::RichText::new(&subscription.name)
+                        .color(MAIN_TEXT)
+                        .size(14.0)
+                        .strong(),
+                );
+                if subscription.new_count > 0 {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{} {}",
+                            subscription.new_count,
+                            localizer
+                                .lookup_single_language("new-videos", None)
+                                .unwrap_or_else(|| "new videos".to_string())
+                        ))
+                        .color(PRIMARY_COLOR)
+                        .size(13.0),
+                    );
+                }
+            });
+            ui.label(
+                egui::RichText::new(format!(
+                    "{} • {} • {}",
+                    subscription.url,
+                    format_label(subscription.format, localizer),
+                    subscription.download_dir
+                ))
+                .color(SECONDARY_TEXT)
+                .size(12.0),
+            );
+
+            ui.horizontal(|ui| {
+                if subscription.checking {
+                    ui.spinner();
+                    ui.label(
+                        egui::RichText::new(
+                            localizer
+                                .lookup_single_language("checking", None)
+                                .unwrap_or_else(|| "Checking...".to_string()),
+                        )
+                        .color(SECONDARY_TEXT)
+                        .size(12.0),
+                    );
+                } else {
+                    ui.label(
+                        egui::RichText::new(last_checked_text(subscription, localizer))
+                            .color(SECONDARY_TEXT)
+                            .size(12.0),
+                    );
+                }
+
+                let check = egui::Button::new(
+                    localizer
+                        .lookup_single_language("check-now", None)
+                        .unwrap_or_else(|| "Check now".to_string()),
+                );
+                if ui.add_enabled(!subscription.checking, check).clicked() {
+                    *action = Some(SubscriptionAction::Check(subscription.id));
+                }
+                if ui
+                    .button(
+                        localizer
+                            .lookup_single_language("remove-button", None)
+                            .unwrap_or_else(|| "Remove".to_string()),
+                    )
+                    .clicked()
+                {
+                    *action = Some(SubscriptionAction::Remove(subscription.id));
+                }
+            });
+
+            if let Some(error) = &subscription.error {
+                ui.label(
+                    egui::RichText::new(format!("Error: {}", error))
+                        .color(TEXT_ERROR)
+                        .size(12.0),
+                );
+            }
+        });
+}
+
+// Subscriptions tab: the add form, the check interval and every subscription
+pub fn render_subscriptions(
+    ui: &mut egui::Ui,
+    state: &mut AppState,
+    localizer: &Localizations,
+) -> Option<SubscriptionAction> {
+    let mut action = None;
+
+    render_form(ui, state, localizer, &mut action);
+    ui.add_space(10.0);
+
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new(
+                localizer
+                    .lookup_single_language("check-interval", None)
+                    .unwrap_or_else(|| "Check every (minutes):".to_string()),
+            )
+            .color(MAIN_TEXT)
+            .size(14.0),
+        );
+        ui.add(
+            egui::Slider::new(
+                &mut state.settings.check_interval_minutes,
+                MIN_CHECK_INTERVAL_MINUTES..=MAX_CHECK_INTERVAL_MINUTES,
+            )
+            .logarithmic(true),
+        );
+    });
+    ui.add_space(10.0);
+
+    egui::ScrollArea::vertical()
+        .id_source("subscription_list")
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            for subscription in state.subscriptions.subscriptions() {
+                render_subscription(ui, subscription, localizer, &mut action);
+                ui.add_space(6.0);
+            }
+        });
+
+    action
+}
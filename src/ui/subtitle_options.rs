@@ -0,0 +1,207 @@
+use eframe::egui;
+
+use crate::localizations::Localizations;
+use crate::models::{AppState, DownloadFormat, SubtitleFormat};
+
+use crate::theme::*;
+
+const FORMATS: [(SubtitleFormat, &str); 3] = [
+    (SubtitleFormat::Srt, "SRT"),
+    (SubtitleFormat::Vtt, "VTT"),
+    (SubtitleFormat::Ass, "ASS"),
+];
+
+fn language_checkboxes(
+    ui: &mut egui::Ui,
+    languages: &[(String, Option<String>)],
+    picked: &mut Vec<String>,
+) {
+    ui.horizontal_wrapped(|ui| {
+        for (code, name) in languages {
+            let label = match name {
+                Some(name) => format!("{} ({})", name, code),
+                None => code.clone(),
+            };
+            let mut checked = picked.contains(code);
+            if ui.checkbox(&mut checked, label).changed() {
+                if checked {
+                    picked.push(code.clone());
+                } else {
+                    picked.retain(|picked| picked != code);
+                }
+            }
+        }
+    });
+}
+
+// Subtitle languages are picked from those the fetched URL offers and kept
+// for later downloads, so a series of lectures only needs picking once
+pub fn render_subtitle_options(ui: &mut egui::Ui, state: &mut AppState, localizer: &Localizations) {
+    egui::CollapsingHeader::new(
+        egui::RichText::new(
+            localizer
+                .lookup_single_language("subtitles-title", None)
+                .unwrap_or_else(|| "Subtitles".to_string()),
+        )
+        .color(MAIN_TEXT)
+        .size(14.0),
+    )
+    .id_source("subtitle_options")
+    .show(ui, |ui| {
+        let subtitles = &mut state.settings.subtitles;
+        let metadata = state
+            .preview
+            .as_ref()
+            .filter(|preview| preview.url == state.url.trim())
+            .and_then(|preview| preview.metadata.as_ref());
+
+        ui.checkbox(
+            &mut subtitles.automatic,
+            egui::RichText::new(
+                localizer
+                    .lookup_single_language("subtitles-automatic", None)
+                    .unwrap_or_else(|| "Include automatic captions".to_string()),
+            )
+            .color(MAIN_TEXT)
+            .size(14.0),
+        );
+
+        match metadata {
+            Some(metadata) => {
+                let manual = metadata.subtitle_languages(false);
+                let automatic = metadata.subtitle_languages(true);
+                if manual.is_empty() && (automatic.is_empty() || !subtitles.automatic) {
+                    ui.label(
+                        egui::RichText::new(
+                            localizer
+                                .lookup_single_language("subtitles-none", None)
+                                .unwrap_or_else(|| {
+                                    "No subtitles available for this URL".to_string()
+                                }),
+                        )
+                        .color(SECONDARY_TEXT)
+                        .size(13.0),
+                    );
+                }
+                if !manual.is_empty() {
+                    language_checkboxes(ui, &manual, &mut subtitles.languages);
+                }
+                // Sites like YouTube offer machine translations into every language
+                if subtitles.automatic && !automatic.is_empty() {
+                    ui.label(
+                        egui::RichText::new(
+                            localizer
+                                .lookup_single_language("subtitles-automatic-list", None)
+                                .unwrap_or_else(|| "Automatic captions:".to_string()),
+                        )
+                        .color(SECONDARY_TEXT)
+                        .size(13.0),
+                    );
+                    egui::ScrollArea::vertical()
+                        .id_source("automatic_captions")
+                        .max_height(120.0)
+                        .show(ui, |ui| {
+                            language_checkboxes(ui, &automatic, &mut subtitles.languages);
+                        });
+                }
+            }
+            None => {
+                ui.label(
+                    egui::RichText::new(
+                        localizer
+                            .lookup_single_language("subtitles-hint", None)
+                            .unwrap_or_else(|| {
+                                "Fetch info for a URL to list its subtitle languages".to_string()
+                            }),
+                    )
+                    .color(SECONDARY_TEXT)
+                    .size(13.0),
+                );
+            }
+        }
+
+        ui.horizontal(|ui| {
+            let languages = if subtitles.languages.is_empty() {
+                "—".to_string()
+            } else {
+                subtitles.languages.join(", ")
+            };
+            ui.label(
+                egui::RichText::new(format!(
+                    "{} {}",
+                    localizer
+                        .lookup_single_language("subtitles-selected", None)
+                        .unwrap_or_else(|| "Languages:".to_string()),
+                    languages
+                ))
+                .color(MAIN_TEXT)
+                .size(13.0),
+            );
+            if !subtitles.languages.is_empty() {
+                let clear_label = localizer
+                    .lookup_single_language("clear-button", None)
+                    .unwrap_or_else(|| "Clear".to_string());
+                if ui.small_button(clear_label).clicked() {
+                    subtitles.languages.clear();
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.radio_value(
+                &mut subtitles.embed,
+                false,
+                egui::RichText::new(
+                    localizer
+                        .lookup_single_language("subtitles-sidecar", None)
+                        .unwrap_or_else(|| "Separate files".to_string()),
+                )
+                .color(MAIN_TEXT)
+                .size(14.0),
+            );
+            ui.radio_value(
+                &mut subtitles.embed,
+                true,
+                egui::RichText::new(
+                    localizer
+                        .lookup_single_language("subtitles-embed", None)
+                        .unwrap_or_else(|| "Embed into the MP4".to_string()),
+                )
+                .color(MAIN_TEXT)
+                .size(14.0),
+            );
+        });
+
+        // Embedded tracks are converted to whatever the container holds
+        let sidecar = !subtitles.embed || state.format == DownloadFormat::MP3;
+        ui.add_enabled_ui(sidecar, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(
+                    egui::RichText::new(
+                        localizer
+                            .lookup_single_language("subtitles-format", None)
+                            .unwrap_or_else(|| "File format:".to_string()),
+                    )
+                    .color(MAIN_TEXT)
+                    .size(14.0),
+                );
+                for (format, label) in FORMATS {
+                    ui.radio_value(&mut subtitles.format, format, label);
+                }
+            });
+        });
+        if subtitles.embed && state.format == DownloadFormat::MP3 {
+            ui.label(
+                egui::RichText::new(
+                    localizer
+                        .lookup_single_language("subtitles-mp3", None)
+                        .unwrap_or_else(|| {
+                            "MP3 files can't hold subtitles, so they are saved as separate files".to_string()
+                        }),
+                )
+                .color(SECONDARY_TEXT)
+                .size(12.0),
+            );
+        }
+    });
+}
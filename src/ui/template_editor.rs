@@ -0,0 +1,147 @@
+use eframe::egui;
+
+use crate::localizations::Localizations;
+use crate::models::AppState;
+use crate::template::{self, DEFAULT_OUTPUT_TEMPLATE, TEMPLATE_FIELDS};
+
+use crate::theme::*;
+
+// Inserts `text` at a character position, returning the position after it
+fn insert_at(draft: &mut String, cursor: Option<usize>, text: &str) -> usize {
+    let chars = draft.chars().count();
+    let position = cursor.unwrap_or(chars).min(chars);
+    let byte = draft
+        .char_indices()
+        .nth(position)
+        .map(|(byte, _)| byte)
+        .unwrap_or(draft.len());
+    draft.insert_str(byte, text);
+    position + text.chars().count()
+}
+
+// Editor for the yt-dlp output template. The draft is copied to the
+// settings only while it validates, so a half-typed field never reaches
+// a download
+pub fn render_template_editor(ui: &mut egui::Ui, state: &mut AppState, localizer: &Localizations) {
+    egui::CollapsingHeader::new(
+        egui::RichText::new(
+            localizer
+                .lookup_single_language("template-title", None)
+                .unwrap_or_else(|| "Filename template".to_string()),
+        )
+        .color(MAIN_TEXT)
+        .size(14.0),
+    )
+    .id_source("template_editor")
+    .show(ui, |ui| {
+        let output = egui::TextEdit::singleline(&mut state.template_draft)
+            .font(egui::TextStyle::Monospace)
+            .desired_width(f32::INFINITY)
+            .show(ui);
+        if let Some(range) = output.cursor_range {
+            state.template_cursor = Some(range.primary.ccursor.index);
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label(
+                egui::RichText::new(
+                    localizer
+                        .lookup_single_language("template-insert", None)
+                        .unwrap_or_else(|| "Insert:".to_string()),
+                )
+                .color(SECONDARY_TEXT)
+                .size(13.0),
+            );
+            for field in TEMPLATE_FIELDS {
+                if ui.small_button(field).clicked() {
+                    let cursor = insert_at(
+                        &mut state.template_draft,
+                        state.template_cursor,
+                        &format!("%({})s", field),
+                    );
+                    state.template_cursor = Some(cursor);
+                }
+            }
+            let reset_label = localizer
+                .lookup_single_language("template-reset", None)
+                .unwrap_or_else(|| "Reset".to_string());
+            if ui.small_button(reset_label).clicked() {
+                state.template_draft = DEFAULT_OUTPUT_TEMPLATE.to_string();
+                state.template_cursor = None;
+            }
+        });
+
+        let validation = template::validate(&state.template_draft);
+        match &validation.error {
+            Some(error) => {
+                ui.label(egui::RichText::new(error).color(TEXT_ERROR).size(13.0));
+            }
+            None => {
+                if state.settings.output_template != state.template_draft {
+                    state.settings.output_template = state.template_draft.clone();
+                }
+            }
+        }
+        for warning in &validation.warnings {
+            ui.label(
+                egui::RichText::new(format!("⚠ {}", warning))
+                    .color(SECONDARY_TEXT)
+                    .size(13.0),
+            );
+        }
+        if validation.error.is_some() {
+            return;
+        }
+
+        // Resolved against the metadata fetched for the current URL
+        let metadata = state
+            .preview
+            .as_ref()
+            .filter(|preview| preview.url == state.url.trim())
+            .and_then(|preview| preview.metadata.as_ref());
+        match metadata {
+            Some(metadata) => match template::preview(&state.template_draft, metadata, state.format) {
+                Ok(name) => {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{} {}",
+                            localizer
+                                .lookup_single_language("template-preview", None)
+                                .unwrap_or_else(|| "Saved as:".to_string()),
+                            name
+                        ))
+                        .color(MAIN_TEXT)
+                        .size(13.0),
+                    );
+                }
+                // yt-dlp still uses the template as written
+                Err(reason) => {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{} ({})",
+                            localizer
+                                .lookup_single_language("template-preview-unavailable", None)
+                                .unwrap_or_else(|| "Preview unavailable".to_string()),
+                            reason
+                        ))
+                        .color(SECONDARY_TEXT)
+                        .size(13.0),
+                    );
+                }
+            },
+            None => {
+                ui.label(
+                    egui::RichText::new(
+                        localizer
+                            .lookup_single_language("template-preview-hint", None)
+                            .unwrap_or_else(|| {
+                                "Fetch info for a URL to preview the file name".to_string()
+                            }),
+                    )
+                    .color(SECONDARY_TEXT)
+                    .size(13.0),
+                );
+            }
+        }
+    });
+}